@@ -1,10 +1,10 @@
 use anyhow::{bail, Context, Result};
-use std::ops::Mul;
+use std::ops::{Add, Mul};
 
 /// Represents a color with the RGB color model.
 ///
 /// See [Wikipedia](https://en.wikipedia.org/wiki/RGB_color_model).
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Color {
     r: u8,
     g: u8,
@@ -132,6 +132,50 @@ impl Mul<f32> for Color {
     }
 }
 
+/// Multiplies a [`Color`] component-wise with another, e.g. to modulate it with the color of a light.
+///
+/// ```
+///# use tilemap::math::color::{Color, WHITE};
+/// let color = Color::from_rgb(0, 100, 255);
+///
+/// assert_eq!(color * WHITE, color);
+/// assert_eq!(color * Color::from_rgb(255, 51, 0), Color::from_rgb(0, 20, 0));
+/// ```
+impl Mul<Color> for Color {
+    type Output = Self;
+
+    fn mul(self, other: Color) -> Color {
+        let mul = |a: u8, b: u8| (a as u32 * b as u32 / 255) as u8;
+
+        Color::from_rgb(
+            mul(self.r, other.r),
+            mul(self.g, other.g),
+            mul(self.b, other.b),
+        )
+    }
+}
+
+/// Adds 2 [`colors`](Color) component-wise, e.g. to combine lights.
+///
+/// ```
+///# use tilemap::math::color::Color;
+/// let a = Color::from_rgb(0, 100, 200);
+/// let b = Color::from_rgb(10, 20, 100);
+///
+/// assert_eq!(a + b, Color::from_rgb(10, 120, 255));
+/// ```
+impl Add<Color> for Color {
+    type Output = Self;
+
+    fn add(self, other: Color) -> Color {
+        Color::from_rgb(
+            self.r.saturating_add(other.r),
+            self.g.saturating_add(other.g),
+            self.b.saturating_add(other.b),
+        )
+    }
+}
+
 pub const BLACK: Color = Color::from_rgb(0, 0, 0);
 pub const BLUE: Color = Color::from_rgb(0, 0, 255);
 pub const CYAN: Color = Color::from_rgb(0, 255, 255);
@@ -19,7 +19,7 @@ use std::ops::{Add, Div, Mul, Sub};
 ///   v
 /// y-axis
 /// ```
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
 pub struct Point2d {
     pub x: i32,
     pub y: i32,
@@ -0,0 +1,59 @@
+use self::Layer::*;
+use std::slice::Iter;
+
+/// The layers of a rendered tilemap, ordered from bottom to top.
+///
+/// The 3d views render solids, walls & nodes in the order of the painter's algorithm, so they alternate.
+/// Renderers that group the output by layer must keep the order of the calls,
+/// e.g. by starting a new group each time the layer changes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Layer {
+    Floors,
+    Shadows,
+    Solids,
+    Walls,
+    Nodes,
+    Grid,
+    Overlays,
+}
+
+impl Layer {
+    /// Iterates over all layers from bottom to top.
+    pub fn iterator() -> Iter<'static, Layer> {
+        static LAYERS: [Layer; 7] = [Floors, Shadows, Solids, Walls, Nodes, Grid, Overlays];
+        LAYERS.iter()
+    }
+
+    /// Returns the name of the layer.
+    ///
+    /// ```
+    ///# use tilemap::port::layer::Layer;
+    /// assert_eq!(Layer::Walls.get_name(), "walls");
+    /// ```
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Floors => "floors",
+            Shadows => "shadows",
+            Solids => "solids",
+            Walls => "walls",
+            Nodes => "nodes",
+            Grid => "grid",
+            Overlays => "overlays",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iterator_is_sorted() {
+        let layers: Vec<Layer> = Layer::iterator().copied().collect();
+        let mut sorted = layers.clone();
+        sorted.sort();
+
+        assert_eq!(layers.len(), 7);
+        assert_eq!(layers, sorted);
+    }
+}
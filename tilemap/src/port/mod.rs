@@ -1 +1,3 @@
+pub mod layer;
 pub mod renderer;
+pub mod style;
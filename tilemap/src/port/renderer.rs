@@ -1,6 +1,13 @@
 use crate::math::color::Color;
 use crate::math::point2d::Point2d;
 use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
+use crate::port::style::StyleRef;
+use crate::renderer::style::aab::Outline;
+use crate::renderer::style::grid::LineStyle;
+use crate::renderer::style::image::Image;
+use crate::renderer::style::pattern::Pattern;
+use crate::tilemap::selection::Selection;
 
 pub trait Renderer {
     /// Renders an axis aligned rectangle.
@@ -16,6 +23,94 @@ pub trait Renderer {
         color: Color,
     );
 
+    /// Renders a transformed rectangle filled with a [`Pattern`] on top of a background color.
+    /// The points are ordered counter-clockwise. p0 is the origin of the pattern,
+    /// p1 is the end of its y-axis & p3 the end of its x-axis.
+    /// The tile is the position in the tilemap, which affects [`random patterns`](Pattern::varies_per_tile).
+    /// Renders only the background color by default.
+    #[allow(clippy::too_many_arguments)]
+    fn render_pattern(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        _pattern: &Pattern,
+        _tile: Point2d,
+        background: Color,
+    ) {
+        self.render_transformed_rectangle(p0, p1, p2, p3, background);
+    }
+
+    /// Renders an [`Image`] stretched across a transformed rectangle.
+    /// The points are ordered counter-clockwise. p0 is the top left corner of the image,
+    /// p1 its bottom left & p3 its top right corner.
+    /// Renders only the background color by default.
+    fn render_image(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        _image: &Image,
+        background: Color,
+    ) {
+        self.render_transformed_rectangle(p0, p1, p2, p3, background);
+    }
+
+    /// Renders a convex polygon. The points are ordered counter-clockwise.
+    /// Renders a fan of triangles from the first point by default.
+    fn render_polygon(&mut self, points: &[Point2d], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+
+        for i in 1..(points.len() - 1) {
+            let p0 = points[0];
+            let p1 = points[i];
+            let p2 = points[i + 1];
+            self.render_transformed_rectangle(p0, p1, p2, p2, color);
+        }
+    }
+
+    /// Renders a convex polygon, that darkens everything rendered below it, like a shadow.
+    /// The points are ordered counter-clockwise & the opacity is between 0 & 1.
+    /// Overlapping shadows of consecutive calls darken only once.
+    /// Ignored by default, because an opaque polygon would hide the floor.
+    fn render_shadow(&mut self, _points: &[Point2d], _color: Color, _opacity: f32) {}
+
     /// Renders a line between 2 points.
     fn render_line(&mut self, p0: Point2d, p1: Point2d, color: Color);
+
+    /// Renders a line between 2 points with a [`LineStyle`].
+    /// Renders a default line with its color by default.
+    fn render_styled_line(&mut self, p0: Point2d, p1: Point2d, style: &LineStyle) {
+        self.render_line(p0, p1, style.get_color());
+    }
+
+    /// Renders the [`Outline`] of a closed polygon without filling it.
+    /// Renders a line per edge by default.
+    fn render_outline(&mut self, points: &[Point2d], outline: &Outline) {
+        let style = LineStyle::solid(outline.get_color(), outline.get_width());
+
+        for (index, point) in points.iter().enumerate() {
+            self.render_styled_line(*point, points[(index + 1) % points.len()], &style);
+        }
+    }
+
+    /// Renders a text centered at a point.
+    /// Ignored by default.
+    fn render_text(&mut self, _center: Point2d, _text: &str, _size: u32, _color: Color) {}
+
+    /// Informs the renderer, that the following calls render a specific element of the tilemap.
+    /// Ignored by default.
+    fn start_selection(&mut self, _selection: Selection) {}
+
+    /// Informs the renderer, that the following calls belong to a specific [`Layer`].
+    /// Ignored by default.
+    fn set_layer(&mut self, _layer: Layer) {}
+
+    /// Informs the renderer, which style determines the color of the following calls.
+    /// Ignored by default.
+    fn set_style(&mut self, _style: StyleRef) {}
 }
@@ -0,0 +1,87 @@
+/// The types of styles.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum StyleKind {
+    Floor,
+    Grid,
+    Node,
+    Shadow,
+    Solid,
+    Wall,
+}
+
+impl StyleKind {
+    /// Returns the name of the style type.
+    ///
+    /// ```
+    ///# use tilemap::port::style::StyleKind;
+    /// assert_eq!(StyleKind::Wall.get_name(), "wall");
+    /// ```
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            StyleKind::Floor => "floor",
+            StyleKind::Grid => "grid",
+            StyleKind::Node => "node",
+            StyleKind::Shadow => "shadow",
+            StyleKind::Solid => "solid",
+            StyleKind::Wall => "wall",
+        }
+    }
+}
+
+/// The faces of a [`box`](crate::renderer::style::aab::BoxStyle).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Face {
+    Top,
+    Front,
+    Side,
+}
+
+impl Face {
+    /// Returns the name of the face.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Face::Top => "top",
+            Face::Front => "front",
+            Face::Side => "side",
+        }
+    }
+}
+
+/// Identifies the style & its face, that determine the color of the rendered elements.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StyleRef<'a> {
+    kind: StyleKind,
+    name: &'a str,
+    face: Option<Face>,
+}
+
+impl<'a> StyleRef<'a> {
+    pub fn new(kind: StyleKind, name: &'a str) -> Self {
+        StyleRef {
+            kind,
+            name,
+            face: None,
+        }
+    }
+
+    /// Returns a reference to a specific face of the same style.
+    pub fn with_face(&self, face: Face) -> Self {
+        StyleRef {
+            kind: self.kind,
+            name: self.name,
+            face: Some(face),
+        }
+    }
+
+    pub fn get_kind(&self) -> StyleKind {
+        self.kind
+    }
+
+    pub fn get_name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn get_face(&self) -> Option<Face> {
+        self.face
+    }
+}
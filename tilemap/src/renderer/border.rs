@@ -1,3 +1,4 @@
+use crate::math::side::Side;
 use crate::math::size2d::Size2d;
 use crate::renderer::node::Node;
 use crate::tilemap::node::{
@@ -14,7 +15,9 @@ pub fn calculate_horizontal_border(
 ) -> (i32, u32) {
     let start_index = get_start_of_horizontal_border(border_index, y);
     let end_index = get_end_of_horizontal_border(border_index, y);
-    calculate_border(nodes, tile_size, start_index, end_index)
+    let start_extent = nodes[start_index].calculate_extent(Side::Right);
+    let end_extent = nodes[end_index].calculate_extent(Side::Left);
+    calculate_border(tile_size, start_extent, end_extent)
 }
 
 /// Calculates the the start offset & length of a vertical [`border`](crate::tilemap::border::Border).
@@ -26,17 +29,14 @@ pub fn calculate_vertical_border(
 ) -> (i32, u32) {
     let start_index = get_start_of_vertical_border(border_index);
     let end_index = get_end_of_vertical_border(size, border_index);
-    calculate_border(nodes, tile_size, start_index, end_index)
+    let start_extent = nodes[start_index].calculate_extent(Side::Front);
+    let end_extent = nodes[end_index].calculate_extent(Side::Back);
+    calculate_border(tile_size, start_extent, end_extent)
 }
 
-/// Calculates the the start offset & length of a [`border`](crate::tilemap::border::Border).
-fn calculate_border(
-    nodes: &[Node],
-    tile_size: u32,
-    start_index: usize,
-    end_index: usize,
-) -> (i32, u32) {
-    let start_half = nodes[start_index].calculate_half();
-    let end_half = nodes[end_index].calculate_half();
-    (start_half as i32, tile_size - (start_half + end_half))
+/// Calculates the the start offset & length of a [`border`](crate::tilemap::border::Border)
+/// shortened by the nodes at both ends.
+fn calculate_border(tile_size: u32, start_extent: i32, end_extent: i32) -> (i32, u32) {
+    let length = tile_size as i32 - start_extent - end_extent;
+    (start_extent, length.max(0) as u32)
 }
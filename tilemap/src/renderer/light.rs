@@ -0,0 +1,432 @@
+use crate::math::color::{Color, WHITE};
+use crate::math::side::Side;
+use crate::math::size2d::Size2d;
+use crate::renderer::style::aab::BoxStyle;
+use crate::tilemap::border::Border;
+use crate::tilemap::light::{Light, Lighting};
+use crate::tilemap::node::get_nodes_size;
+use crate::tilemap::tile::Tile;
+use crate::tilemap::tilemap2d::Tilemap2d;
+use crate::tilemap::visibility::VisibilityMask;
+
+/// The light reaching the visible faces of a box.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FaceLights {
+    pub top: Color,
+    pub front: Color,
+    pub side: Color,
+}
+
+impl FaceLights {
+    /// Returns the same light for all faces.
+    pub fn uniform(light: Color) -> Self {
+        FaceLights {
+            top: light,
+            front: light,
+            side: light,
+        }
+    }
+
+    /// Modulates the colors of a [`BoxStyle`] with the light of each face.
+    pub fn illuminate(&self, style: &BoxStyle) -> BoxStyle {
+        style.recolor(
+            *style.get_front_color() * self.front,
+            *style.get_side_color() * self.side,
+            *style.get_top_color() * self.top,
+        )
+    }
+}
+
+/// The light level of each [`tile`](crate::tilemap::tile::Tile) after propagating the
+/// [`lights`](Light) of a [`tilemap`](Tilemap2d). Walls & solid tiles block the light.
+///
+/// A tilemap without [`lighting`](Lighting) is fully lit.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LightMap {
+    size: Size2d,
+    ambient: Color,
+    tiles: Vec<Color>,
+}
+
+impl LightMap {
+    pub fn new(tilemap: &Tilemap2d) -> Self {
+        let size = tilemap.get_size();
+
+        match tilemap.get_lighting() {
+            None => LightMap {
+                size,
+                ambient: WHITE,
+                tiles: vec![WHITE; size.count()],
+            },
+            Some(lighting) => LightMap {
+                size,
+                ambient: lighting.get_ambient(),
+                tiles: (0..size.count())
+                    .map(|index| calculate_tile_light(tilemap, lighting, index))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Dims the tiles seen before & darkens unseen tiles,
+    /// so they don't brighten the walls & nodes next to them.
+    pub fn apply_visibility(&mut self, mask: &VisibilityMask) {
+        for (index, light) in self.tiles.iter_mut().enumerate() {
+            *light = *light * mask.get_visibility(index).get_light();
+        }
+    }
+
+    /// Returns the light of the tile at a position or the ambient light outside the tilemap.
+    pub fn get_light(&self, x: i32, y: i32) -> Color {
+        if x < 0 || y < 0 || x >= self.size.width() as i32 || y >= self.size.height() as i32 {
+            return self.ambient;
+        }
+
+        self.tiles[self.size.convert_x_y(x as u32, y as u32)]
+    }
+
+    /// Returns the light of the neighbor on a specific side of a tile.
+    fn get_neighbor_light(&self, x: i32, y: i32, side: Side) -> Color {
+        match side {
+            Side::Back => self.get_light(x, y - 1),
+            Side::Left => self.get_light(x - 1, y),
+            Side::Front => self.get_light(x, y + 1),
+            Side::Right => self.get_light(x + 1, y),
+        }
+    }
+
+    pub fn get_tile_light(&self, tile_index: usize) -> Color {
+        self.tiles[tile_index]
+    }
+
+    /// Returns the light on the faces of a solid tile. Each face is lit by the neighbor it faces.
+    pub fn get_solid_lights(&self, tile_index: usize) -> FaceLights {
+        self.get_solid_lights_facing(tile_index, Side::Front, Side::Right)
+    }
+
+    /// Returns the light on the faces of a solid tile,
+    /// where the front & side faces point towards specific sides of the tile.
+    pub fn get_solid_lights_facing(
+        &self,
+        tile_index: usize,
+        front: Side,
+        side: Side,
+    ) -> FaceLights {
+        let x = self.size.to_x(tile_index);
+        let y = self.size.to_y(tile_index);
+
+        FaceLights {
+            top: self.get_light(x, y),
+            front: self.get_neighbor_light(x, y, front),
+            side: self.get_neighbor_light(x, y, side),
+        }
+    }
+
+    /// Returns the light on the faces of the wall on a specific side of a tile.
+    /// The visible faces of walls at the back or left are lit by the tile.
+    pub fn get_wall_lights(&self, tile_index: usize, side: Side) -> FaceLights {
+        self.get_wall_lights_facing(tile_index, side, Side::Front, Side::Right)
+    }
+
+    /// Returns the light on the faces of the wall on a specific side of a tile,
+    /// where the front & side faces of the wall point towards specific sides.
+    ///
+    /// A face pointing towards the tile is lit by it & one pointing away by the neighbor.
+    /// All other faces are lit by the brighter one.
+    pub fn get_wall_lights_facing(
+        &self,
+        tile_index: usize,
+        side: Side,
+        front: Side,
+        side_face: Side,
+    ) -> FaceLights {
+        let x = self.size.to_x(tile_index);
+        let y = self.size.to_y(tile_index);
+        let tile = self.get_light(x, y);
+        let other = self.get_neighbor_light(x, y, side);
+        let brightest = max(tile, other);
+        let get_face_light = |face: Side| {
+            if face == side {
+                other
+            } else if face.is_straight(side) {
+                tile
+            } else {
+                brightest
+            }
+        };
+
+        FaceLights {
+            top: brightest,
+            front: get_face_light(front),
+            side: get_face_light(side_face),
+        }
+    }
+
+    /// Returns the light on the faces of a node, which is lit by the brightest of the 4 tiles around it.
+    pub fn get_node_lights(&self, node_index: usize) -> FaceLights {
+        let nodes_size = get_nodes_size(self.size);
+        let x = nodes_size.to_x(node_index);
+        let y = nodes_size.to_y(node_index);
+        let light = max(
+            max(self.get_light(x - 1, y - 1), self.get_light(x, y - 1)),
+            max(self.get_light(x - 1, y), self.get_light(x, y)),
+        );
+
+        FaceLights::uniform(light)
+    }
+}
+
+/// Returns the component-wise maximum of 2 lights.
+fn max(a: Color, b: Color) -> Color {
+    Color::from_rgb(a.r().max(b.r()), a.g().max(b.g()), a.b().max(b.b()))
+}
+
+fn calculate_tile_light(tilemap: &Tilemap2d, lighting: &Lighting, index: usize) -> Color {
+    let size = tilemap.get_size();
+    let x = size.to_x(index);
+    let y = size.to_y(index);
+
+    lighting
+        .get_lights()
+        .iter()
+        .fold(lighting.get_ambient(), |sum, light| {
+            sum + calculate_light(tilemap, light, x, y)
+        })
+}
+
+/// Calculates the light reaching the center of a tile from a [`Light`].
+fn calculate_light(tilemap: &Tilemap2d, light: &Light, x: i32, y: i32) -> Color {
+    let position = light.get_position();
+    let distance = (position.x as f32 - x as f32).hypot(position.y as f32 - y as f32);
+    let radius = light.get_radius() as f32;
+
+    if distance > radius || !is_visible(tilemap, position.x, position.y, x, y) {
+        return Color::gray(0);
+    }
+
+    let falloff = 1.0 - distance / radius.max(f32::EPSILON);
+    light.get_color() * (light.get_intensity() as f32 / 100.0 * falloff)
+}
+
+/// Is the center of the end tile visible from the center of the start tile?
+/// Walks along the line through all tiles it touches & stops at walls & solid tiles.
+fn is_visible(tilemap: &Tilemap2d, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> bool {
+    let size = tilemap.get_size();
+    let dx = end_x - start_x;
+    let dy = end_y - start_y;
+    let step_x = dx.signum();
+    let step_y = dy.signum();
+    let delta_x = 1.0 / dx.abs() as f32;
+    let delta_y = 1.0 / dy.abs() as f32;
+    let mut next_x = 0.5 * delta_x;
+    let mut next_y = 0.5 * delta_y;
+    let mut x = start_x;
+    let mut y = start_y;
+
+    if start_x < 0
+        || start_y < 0
+        || start_x >= size.width() as i32
+        || start_y >= size.height() as i32
+    {
+        return false;
+    }
+
+    while x != end_x || y != end_y {
+        let index = size.convert_x_y(x as u32, y as u32);
+
+        let side = if next_x <= next_y {
+            next_x += delta_x;
+            x += step_x;
+            if step_x > 0 {
+                Side::Right
+            } else {
+                Side::Left
+            }
+        } else {
+            next_y += delta_y;
+            y += step_y;
+            if step_y > 0 {
+                Side::Front
+            } else {
+                Side::Back
+            }
+        };
+
+        if let Border::Wall(_) = tilemap.get_border(index, side) {
+            return false;
+        }
+
+        let is_end = x == end_x && y == end_y;
+
+        if !is_end {
+            if let Tile::Solid(_) = tilemap.get_tile(size.convert_x_y(x as u32, y as u32)) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::BLACK;
+    use crate::math::point2d::Point2d;
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::tile::FloorId;
+    use crate::tilemap::tile::SolidId;
+    use crate::tilemap::visibility::Visibility;
+
+    const AMBIENT: Color = Color::gray(10);
+
+    #[test]
+    fn test_without_lighting() {
+        let tilemap = Tilemap2d::default(Size2d::new(2, 3), Tile::Floor(FloorId(0))).unwrap();
+        let light_map = LightMap::new(&tilemap);
+
+        for index in 0..6 {
+            assert_eq!(light_map.get_tile_light(index), WHITE);
+        }
+    }
+
+    #[test]
+    fn test_falloff() {
+        let tilemap = create_tilemap(Size2d::new(5, 1), Point2d::new(0, 0), 4);
+        let light_map = LightMap::new(&tilemap);
+
+        assert_eq!(light_map.get_tile_light(0), Color::gray(210));
+        assert_eq!(light_map.get_tile_light(1), Color::gray(160));
+        assert_eq!(light_map.get_tile_light(2), Color::gray(110));
+        assert_eq!(light_map.get_tile_light(3), Color::gray(60));
+        assert_eq!(light_map.get_tile_light(4), AMBIENT);
+    }
+
+    #[test]
+    fn test_wall_blocks_light() {
+        let mut tilemap = create_tilemap(Size2d::new(3, 3), Point2d::new(1, 1), 4);
+        tilemap.set_border(4, Side::Right, Border::Wall(WallId(0)));
+        let light_map = LightMap::new(&tilemap);
+
+        assert_eq!(light_map.get_tile_light(5), AMBIENT);
+        assert_ne!(light_map.get_tile_light(3), AMBIENT);
+        assert_ne!(light_map.get_tile_light(7), AMBIENT);
+    }
+
+    #[test]
+    fn test_solid_blocks_light() {
+        let mut tilemap = create_tilemap(Size2d::new(4, 1), Point2d::new(0, 0), 4);
+        tilemap.set_tile(1, Tile::Solid(SolidId(0)));
+        let light_map = LightMap::new(&tilemap);
+
+        assert_ne!(light_map.get_tile_light(1), AMBIENT);
+        assert_eq!(light_map.get_tile_light(2), AMBIENT);
+        assert_eq!(light_map.get_tile_light(3), AMBIENT);
+    }
+
+    #[test]
+    fn test_wall_lights() {
+        let mut tilemap = create_tilemap(Size2d::new(1, 2), Point2d::new(0, 1), 1);
+        tilemap.set_border(1, Side::Back, Border::Wall(WallId(0)));
+        let light_map = LightMap::new(&tilemap);
+        let lit = Color::gray(210);
+
+        assert_eq!(
+            light_map.get_wall_lights(1, Side::Back),
+            FaceLights {
+                top: lit,
+                front: lit,
+                side: lit,
+            }
+        );
+        assert_eq!(
+            light_map.get_wall_lights(0, Side::Front),
+            FaceLights {
+                top: lit,
+                front: lit,
+                side: lit,
+            }
+        );
+        assert_eq!(
+            light_map.get_wall_lights(0, Side::Back),
+            FaceLights::uniform(AMBIENT)
+        );
+    }
+
+    #[test]
+    fn test_apply_visibility() {
+        let tilemap = Tilemap2d::default(Size2d::new(3, 1), Tile::Floor(FloorId(0))).unwrap();
+        let mask = VisibilityMask::new(
+            Size2d::new(3, 1),
+            vec![Visibility::Visible, Visibility::Seen, Visibility::Unseen],
+        )
+        .unwrap();
+        let mut light_map = LightMap::new(&tilemap);
+
+        light_map.apply_visibility(&mask);
+
+        assert_eq!(light_map.get_tile_light(0), WHITE);
+        assert_eq!(light_map.get_tile_light(1), Color::gray(128));
+        assert_eq!(light_map.get_tile_light(2), BLACK);
+        assert_eq!(
+            light_map.get_wall_lights(1, Side::Right),
+            FaceLights {
+                top: Color::gray(128),
+                front: Color::gray(128),
+                side: BLACK,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lights_facing_other_sides() {
+        let tilemap = Tilemap2d::default(Size2d::new(3, 1), Tile::Floor(FloorId(0))).unwrap();
+        let mask = VisibilityMask::new(
+            Size2d::new(3, 1),
+            vec![Visibility::Visible, Visibility::Seen, Visibility::Unseen],
+        )
+        .unwrap();
+        let mut light_map = LightMap::new(&tilemap);
+
+        light_map.apply_visibility(&mask);
+
+        assert_eq!(
+            light_map.get_solid_lights_facing(1, Side::Left, Side::Right),
+            FaceLights {
+                top: Color::gray(128),
+                front: WHITE,
+                side: BLACK,
+            }
+        );
+        assert_eq!(
+            light_map.get_wall_lights_facing(1, Side::Left, Side::Left, Side::Right),
+            FaceLights {
+                top: WHITE,
+                front: WHITE,
+                side: Color::gray(128),
+            }
+        );
+    }
+
+    #[test]
+    fn test_illuminate() {
+        let lights = FaceLights {
+            top: WHITE,
+            front: Color::gray(0),
+            side: BLACK,
+        };
+        let style = BoxStyle::shaded(WHITE);
+
+        assert_eq!(
+            lights.illuminate(&style),
+            BoxStyle::new(BLACK, BLACK, WHITE)
+        );
+    }
+
+    fn create_tilemap(size: Size2d, position: Point2d, radius: u32) -> Tilemap2d {
+        let mut tilemap = Tilemap2d::default(size, Tile::Floor(FloorId(0))).unwrap();
+        let light = Light::new(position, Color::gray(200), 100, radius);
+        tilemap.set_lighting(Some(Lighting::new(AMBIENT, vec![light])));
+        tilemap
+    }
+}
@@ -1,4 +1,10 @@
 pub mod border;
+pub mod light;
 pub mod node;
+pub mod picker;
+#[cfg(test)]
+pub(crate) mod recorder;
+pub mod shadow;
 pub mod style;
 pub mod view;
+pub mod viewport;
@@ -1,12 +1,15 @@
+use crate::math::point2d::Point2d;
 use crate::math::side::Side;
 use crate::renderer::style::node::NodeStyle;
-use crate::renderer::style::wall::WallStyle;
+use crate::renderer::style::wall::{WallJoin, WallStyle};
+use crate::renderer::style::StyleMgr;
 use crate::tilemap::border::WallId;
 use crate::tilemap::node::get_nodes_size;
 use crate::tilemap::tilemap2d::Tilemap2d;
-use crate::utils::resource::ResourceManager;
+use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::fmt::Debug;
 
 /// Nodes are the 4 corners of each [`tile`](crate::tilemap::tile::Tile)
 /// and the start & end point of each [`border`](crate::tilemap::border::Border).
@@ -19,54 +22,395 @@ pub enum Node<'a> {
     /// A node in the middle of a straight wall that is not rendered.
     InnerNode,
     /// A corner, an end point of a wall or intersection of multiple walls.
-    OuterNode(&'a NodeStyle),
+    /// The node is moved by the [`alignment`](crate::renderer::style::wall::WallAlignment)
+    /// of its dominant wall style along the axes of its walls.
+    OuterNode(&'a NodeStyle, Point2d),
+    /// Walls joined without a node style, because their dominant style has another [`WallJoin`].
+    Joint(Joint<'a>),
 }
 
 impl<'a> Node<'a> {
-    pub fn calculate_half(&self) -> u32 {
+    /// Returns how far the node extends from its center towards a side,
+    /// which shortens the wall on that side.
+    pub fn calculate_extent(&self, side: Side) -> i32 {
         match self {
             Node::NoNode => 0,
             Node::InnerNode => 0,
-            Node::OuterNode(style) => style.get_half(),
+            Node::OuterNode(style, offset) => {
+                let half = style.get_half() as i32;
+
+                match side {
+                    Side::Back => half - offset.y,
+                    Side::Left => half - offset.x,
+                    Side::Front => half + offset.y,
+                    Side::Right => half + offset.x,
+                }
+            }
+            Node::Joint(joint) => match side {
+                Side::Back => -joint.y_range.0.ceil() as i32,
+                Side::Left => -joint.x_range.0.ceil() as i32,
+                Side::Front => joint.y_range.1.floor() as i32,
+                Side::Right => joint.x_range.1.floor() as i32,
+            },
+        }
+    }
+}
+
+/// Fills the gap between the walls at a node with geometry computed from their thicknesses.
+///
+/// The gap covers the vertical walls along the x-axis & the horizontal walls along the y-axis.
+/// The walls end at the gap, so it is empty at the end of a wall or a straight line.
+#[derive(Debug, PartialEq)]
+pub struct Joint<'a> {
+    style: &'a WallStyle,
+    x_range: (f32, f32),
+    y_range: (f32, f32),
+    miter: Option<Miter<'a>>,
+}
+
+/// The 2 walls of a mitered corner & the sides of the node they are on.
+#[derive(Debug, PartialEq)]
+struct Miter<'a> {
+    horizontal: &'a WallStyle,
+    vertical: &'a WallStyle,
+    x_side: Side,
+    y_side: Side,
+}
+
+impl<'a> Joint<'a> {
+    /// Calculates the joint of the walls at a node, where the walls of a style dominate.
+    fn new(
+        styles: &'a StyleMgr,
+        tilemap: &Tilemap2d,
+        index: usize,
+        style: &'a WallStyle,
+        junction: Junction,
+    ) -> Self {
+        let mut x_range = None;
+        let mut y_range = None;
+        let mut horizontal = None;
+        let mut vertical = None;
+
+        for side in Side::iterator() {
+            if let Some(id) = tilemap.get_border_at_node(index, *side).get_wall_style() {
+                let wall = styles.get_wall_style(id);
+
+                match side {
+                    Side::Back | Side::Front => {
+                        x_range = Some(extend_range(x_range, wall));
+                        vertical = Some((wall, *side));
+                    }
+                    Side::Left | Side::Right => {
+                        y_range = Some(extend_range(y_range, wall));
+                        horizontal = Some((wall, *side));
+                    }
+                }
+            }
+        }
+
+        let miter = match (style.get_join(), junction, horizontal, vertical) {
+            (WallJoin::Mitered, Junction::Corner, Some(horizontal), Some(vertical)) => {
+                Some(Miter {
+                    horizontal: horizontal.0,
+                    vertical: vertical.0,
+                    x_side: horizontal.1,
+                    y_side: vertical.1,
+                })
+            }
+            _ => None,
+        };
+
+        Joint {
+            style,
+            x_range: x_range.unwrap_or_default(),
+            y_range: y_range.unwrap_or_default(),
+            miter,
+        }
+    }
+
+    /// Returns the dominant wall style, that fills the gap.
+    pub fn get_style(&self) -> &'a WallStyle {
+        self.style
+    }
+
+    pub fn get_width(&self) -> u32 {
+        (self.x_range.1 - self.x_range.0) as u32
+    }
+
+    pub fn get_depth(&self) -> u32 {
+        (self.y_range.1 - self.y_range.0) as u32
+    }
+
+    /// Returns the parts filling the gap.
+    /// A mitered corner has a triangle per wall & everything else a single rectangle.
+    pub fn get_parts(&self) -> Vec<JointPart<'a>> {
+        if self.get_width() == 0 || self.get_depth() == 0 {
+            return Vec::new();
+        }
+
+        let (x0, x1) = self.x_range;
+        let (y0, y1) = self.y_range;
+
+        match &self.miter {
+            None => vec![JointPart {
+                style: self.style,
+                side: None,
+                corners: vec![(x0, y0), (x0, y1), (x1, y1), (x1, y0)],
+            }],
+            Some(miter) => {
+                // the seam goes from the outer corner to the inner one between the walls
+                let (outer_x, inner_x) = if miter.x_side == Side::Left {
+                    (x1, x0)
+                } else {
+                    (x0, x1)
+                };
+                let (outer_y, inner_y) = if miter.y_side == Side::Back {
+                    (y1, y0)
+                } else {
+                    (y0, y1)
+                };
+                let outer = (outer_x, outer_y);
+                let inner = (inner_x, inner_y);
+
+                vec![
+                    JointPart {
+                        style: miter.horizontal,
+                        side: Some(miter.x_side),
+                        corners: to_counter_clockwise(vec![outer, (inner_x, outer_y), inner]),
+                    },
+                    JointPart {
+                        style: miter.vertical,
+                        side: Some(miter.y_side),
+                        corners: to_counter_clockwise(vec![outer, inner, (outer_x, inner_y)]),
+                    },
+                ]
+            }
+        }
+    }
+}
+
+/// A part of a [`Joint`] with the wall style it is rendered with.
+#[derive(Debug, PartialEq)]
+pub struct JointPart<'a> {
+    style: &'a WallStyle,
+    side: Option<Side>,
+    corners: Vec<(f32, f32)>,
+}
+
+impl<'a> JointPart<'a> {
+    pub fn get_style(&self) -> &'a WallStyle {
+        self.style
+    }
+
+    /// Returns the side of the node with the wall, that the part extends,
+    /// or *None*, if it fills the gap between all the walls.
+    pub fn get_side(&self) -> Option<Side> {
+        self.side
+    }
+
+    /// Returns the corners relative to the node.
+    /// They are ordered like for [`NodeShape::get_corners()`](crate::renderer::style::node::NodeShape::get_corners).
+    pub fn get_corners(&self) -> &[(f32, f32)] {
+        &self.corners
+    }
+}
+
+/// Extends the range covered by the walls along an axis by another wall.
+fn extend_range(range: Option<(f32, f32)>, wall: &WallStyle) -> (f32, f32) {
+    let start = wall.get_start_offset();
+    let end = start + wall.get_thickness() as f32;
+
+    match range {
+        None => (start, end),
+        Some((min, max)) => (min.min(start), max.max(end)),
+    }
+}
+
+/// Orders the corners of a polygon counter-clockwise in the image, where the y-axis points down.
+fn to_counter_clockwise(mut corners: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    let area: f32 = corners
+        .iter()
+        .zip(corners.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+
+    if area > 0.0 {
+        corners.reverse();
+    }
+
+    corners
+}
+
+/// The shape formed by the walls meeting at a node.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Junction {
+    /// The end of a wall.
+    End,
+    /// 2 walls meeting in a straight line.
+    Straight,
+    /// 2 walls meeting at a right angle.
+    Corner,
+    /// 3 walls meeting at the node.
+    TJunction,
+    /// 4 walls meeting at the node.
+    Cross,
+}
+
+impl Junction {
+    /// Returns the junction formed by walls on specific sides of a node, if there are any.
+    ///
+    /// ```
+    ///# use tilemap::math::side::Side::*;
+    ///# use tilemap::renderer::node::Junction;
+    /// assert_eq!(Junction::from_sides(&[]), None);
+    /// assert_eq!(Junction::from_sides(&[Left]), Some(Junction::End));
+    /// assert_eq!(Junction::from_sides(&[Left, Right]), Some(Junction::Straight));
+    /// assert_eq!(Junction::from_sides(&[Back, Right]), Some(Junction::Corner));
+    /// assert_eq!(Junction::from_sides(&[Back, Left, Right]), Some(Junction::TJunction));
+    /// assert_eq!(Junction::from_sides(&[Back, Left, Front, Right]), Some(Junction::Cross));
+    /// ```
+    pub fn from_sides(sides: &[Side]) -> Option<Junction> {
+        match sides {
+            [] => None,
+            [_] => Some(Junction::End),
+            [side0, side1] if side0.is_straight(*side1) => Some(Junction::Straight),
+            [_, _] => Some(Junction::Corner),
+            [_, _, _] => Some(Junction::TJunction),
+            _ => Some(Junction::Cross),
         }
     }
 }
 
+/// Decides, which [`wall style`](crate::renderer::style::wall::WallStyle) determines the node,
+/// where several wall styles meet.
+pub trait NodeDominance: Debug {
+    /// Selects the dominant wall style of a node.
+    /// Each candidate is a wall style & the sides of the node it covers. They are sorted by id & never empty.
+    fn select(&self, candidates: &[(WallId, Vec<Side>)]) -> WallId;
+}
+
+/// The wall style with the most sides dominates & ties go to the smallest id.
+#[derive(Debug, Default)]
+pub struct MostSides;
+
+impl NodeDominance for MostSides {
+    fn select(&self, candidates: &[(WallId, Vec<Side>)]) -> WallId {
+        get_top_styles(candidates.iter().cloned())
+            .iter()
+            .map(|s| s.0)
+            .min()
+            .unwrap()
+    }
+}
+
+/// The wall styles dominate in a fixed order.
+/// Styles missing from the order come after it & are decided by [`MostSides`].
+#[derive(Debug)]
+pub struct WallPriority {
+    order: Vec<WallId>,
+}
+
+impl WallPriority {
+    /// Creates a priority from the most to the least dominant wall style.
+    pub fn new(order: Vec<WallId>) -> Self {
+        WallPriority { order }
+    }
+
+    pub fn get_order(&self) -> &[WallId] {
+        &self.order
+    }
+
+    fn get_rank(&self, id: WallId) -> usize {
+        self.order
+            .iter()
+            .position(|other| *other == id)
+            .unwrap_or(self.order.len())
+    }
+}
+
+impl NodeDominance for WallPriority {
+    fn select(&self, candidates: &[(WallId, Vec<Side>)]) -> WallId {
+        candidates
+            .iter()
+            .min_by_key(|(id, sides)| (self.get_rank(*id), Reverse(sides.len()), *id))
+            .map(|(id, _sides)| *id)
+            .unwrap()
+    }
+}
+
 /// Calculates the [`node`](Node) at each node.
-pub fn calculate_node_styles<'a>(
-    node_styles: &'a ResourceManager<NodeStyle>,
-    wall_styles: &'a ResourceManager<WallStyle>,
-    tilemap: &'a Tilemap2d,
-) -> Vec<Node<'a>> {
-    calculate_dominant_wall_styles(tilemap)
+pub fn calculate_node_styles<'a>(styles: &'a StyleMgr, tilemap: &'a Tilemap2d) -> Vec<Node<'a>> {
+    let node_styles = styles.get_node_styles();
+    let wall_styles = styles.get_wall_styles();
+
+    calculate_dominant_wall_styles(tilemap, styles.get_node_dominance())
         .iter()
-        .map(|o| match o {
+        .enumerate()
+        .map(|(index, o)| match o {
             IdNode::No => Node::NoNode,
             IdNode::Inner => Node::InnerNode,
-            IdNode::Outer(wall_id) => {
-                let node_id = wall_styles.get(*wall_id).get_node_style();
-                Node::OuterNode(node_styles.get(node_id))
+            IdNode::Outer(wall_id, junction) => {
+                let wall_style = wall_styles.get(*wall_id);
+
+                match wall_style.get_join() {
+                    WallJoin::Node => Node::OuterNode(
+                        node_styles.get(wall_style.get_node_style(*junction)),
+                        calculate_node_offset(tilemap, index, wall_style),
+                    ),
+                    WallJoin::Filled | WallJoin::Mitered => {
+                        Node::Joint(Joint::new(styles, tilemap, index, wall_style, *junction))
+                    }
+                }
             }
         })
         .collect()
 }
 
+/// Calculates how much a node is moved by the alignment of its dominant wall style.
+/// The vertical walls move it along the x-axis & the horizontal walls along the y-axis.
+fn calculate_node_offset(tilemap: &Tilemap2d, index: usize, style: &WallStyle) -> Point2d {
+    let offset = style.get_center_offset();
+    let has_wall = |sides: [Side; 2]| {
+        sides.iter().any(|side| {
+            tilemap
+                .get_border_at_node(index, *side)
+                .get_wall_style()
+                .is_some()
+        })
+    };
+    let x = if has_wall([Side::Back, Side::Front]) {
+        offset
+    } else {
+        0
+    };
+    let y = if has_wall([Side::Left, Side::Right]) {
+        offset
+    } else {
+        0
+    };
+
+    Point2d::new(x, y)
+}
+
 #[derive(Debug, PartialEq)]
 enum IdNode {
     No,
     Inner,
-    Outer(WallId),
+    Outer(WallId, Junction),
 }
 
 /// Calculates the dominant [`wall style`](crate::renderer::style::wall::WallStyle) at each node.
-fn calculate_dominant_wall_styles(tilemap: &Tilemap2d) -> Vec<IdNode> {
+fn calculate_dominant_wall_styles(
+    tilemap: &Tilemap2d,
+    dominance: &dyn NodeDominance,
+) -> Vec<IdNode> {
     let size = get_nodes_size(tilemap.get_size());
     let mut node_styles = Vec::with_capacity(size.count());
     let mut index = 0;
 
     for _y in 0..size.height() {
         for _x in 0..size.width() {
-            node_styles.push(calculate_dominant_wall_style(tilemap, index));
+            node_styles.push(calculate_dominant_wall_style(tilemap, dominance, index));
             index += 1;
         }
     }
@@ -75,41 +419,32 @@ fn calculate_dominant_wall_styles(tilemap: &Tilemap2d) -> Vec<IdNode> {
 }
 
 /// Calculates the dominant [`wall style`](crate::renderer::style::wall::WallStyle) at the node.
-fn calculate_dominant_wall_style(tilemap: &Tilemap2d, index: usize) -> IdNode {
-    let sides_per_style = calculate_sides_per_style(tilemap, index);
-    let is_intersection = sides_per_style.len() > 1;
-    let top_styles = get_top_styles(sides_per_style);
-
-    match top_styles.len() {
-        1 => handle_one_style(&top_styles[0], is_intersection),
-        n if n > 1 => IdNode::Outer(top_styles.iter().map(|s| s.0).min().unwrap()),
-        _ => IdNode::No,
-    }
-}
+/// A straight line of a single wall style has no visible node.
+fn calculate_dominant_wall_style(
+    tilemap: &Tilemap2d,
+    dominance: &dyn NodeDominance,
+    index: usize,
+) -> IdNode {
+    let mut candidates: Vec<_> = calculate_sides_per_style(tilemap, index)
+        .into_iter()
+        .collect();
+    candidates.sort_by_key(|(id, _sides)| *id);
+    let sides: Vec<Side> = candidates
+        .iter()
+        .flat_map(|(_id, sides)| sides.iter().copied())
+        .collect();
 
-fn handle_one_style(top_style: &(WallId, Vec<Side>), is_intersection: bool) -> IdNode {
-    if is_inner_node(top_style, is_intersection) {
-        return IdNode::Inner;
+    match Junction::from_sides(&sides) {
+        None => IdNode::No,
+        Some(Junction::Straight) if candidates.len() == 1 => IdNode::Inner,
+        Some(junction) => IdNode::Outer(dominance.select(&candidates), junction),
     }
-
-    IdNode::Outer(top_style.0)
-}
-
-/// Is the node an inner or outer node? Assumes that it is part of a wall.
-fn is_inner_node(top_style: &(WallId, Vec<Side>), is_intersection: bool) -> bool {
-    !is_intersection && top_style.1.len() == 2 && is_straight(top_style)
-}
-
-/// Does the wall style form a straight line at a node?
-fn is_straight(style: &(WallId, Vec<Side>)) -> bool {
-    let side0 = style.1[0];
-    let side1 = style.1[1];
-
-    side0.is_straight(side1)
 }
 
 /// Calculates the [`wall styles`](crate::renderer::style::wall::WallStyle) with the highest count.
-fn get_top_styles(input: HashMap<WallId, Vec<Side>>) -> Vec<(WallId, Vec<Side>)> {
+fn get_top_styles<I: IntoIterator<Item = (WallId, Vec<Side>)>>(
+    input: I,
+) -> Vec<(WallId, Vec<Side>)> {
     let mut max_count = 0;
     let mut top_styles = Vec::new();
 
@@ -155,29 +490,36 @@ fn calculate_sides_per_style(tilemap: &Tilemap2d, node_index: usize) -> HashMap<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::color::PINK;
     use crate::math::side::Side::*;
     use crate::math::size2d::Size2d;
     use crate::renderer::node::IdNode::{Inner, No, Outer};
+    use crate::renderer::style::aab::BoxStyle;
+    use crate::renderer::style::grid::GridStyle;
+    use crate::renderer::style::wall::{JunctionNodes, WallAlignment};
     use crate::tilemap::border::Border::Wall;
     use crate::tilemap::tile::Tile::Empty;
-    use map_macro::map;
+    use crate::tilemap::NodeId;
+    use crate::utils::resource::Resource;
+    use map_macro::hash_map;
+    use Junction::*;
 
     #[test]
     fn test_wall_style_twice_at_node_dominates() {
         let size = Size2d::new(2, 2);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(2, Back, Wall(2));
-        tilemap.set_border(3, Back, Wall(2));
-        tilemap.set_border(3, Left, Wall(3));
+        tilemap.set_border(2, Back, Wall(WallId(2)));
+        tilemap.set_border(3, Back, Wall(WallId(2)));
+        tilemap.set_border(3, Left, Wall(WallId(3)));
 
         #[rustfmt::skip]
         assert_eq!(
-            calculate_dominant_wall_styles(&tilemap),
+            calculate_dominant_wall_styles(&tilemap, &MostSides),
             vec![
                 No, No, No,
-                Outer(2), Outer(2), Outer(2),
-                No, Outer(3), No
+                Outer(WallId(2), End), Outer(WallId(2), TJunction), Outer(WallId(2), End),
+                No, Outer(WallId(3), End), No
             ]
         );
     }
@@ -187,15 +529,15 @@ mod tests {
         let size = Size2d::new(2, 2);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(2, Back, Wall(2));
-        tilemap.set_border(3, Back, Wall(2));
+        tilemap.set_border(2, Back, Wall(WallId(2)));
+        tilemap.set_border(3, Back, Wall(WallId(2)));
 
         #[rustfmt::skip]
         assert_eq!(
-            calculate_dominant_wall_styles(&tilemap),
+            calculate_dominant_wall_styles(&tilemap, &MostSides),
             vec![
                 No, No, No,
-                Outer(2), Inner, Outer(2),
+                Outer(WallId(2), End), Inner, Outer(WallId(2), End),
                 No, No, No,
             ]
         );
@@ -206,22 +548,253 @@ mod tests {
         let size = Size2d::new(2, 2);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(1, Left, Wall(13));
-        tilemap.set_border(2, Back, Wall(12));
-        tilemap.set_border(3, Back, Wall(11));
-        tilemap.set_border(3, Left, Wall(10));
+        tilemap.set_border(1, Left, Wall(WallId(13)));
+        tilemap.set_border(2, Back, Wall(WallId(12)));
+        tilemap.set_border(3, Back, Wall(WallId(11)));
+        tilemap.set_border(3, Left, Wall(WallId(10)));
+
+        #[rustfmt::skip]
+        assert_eq!(
+            calculate_dominant_wall_styles(&tilemap, &MostSides),
+            vec![
+                No, Outer(WallId(13), End), No,
+                Outer(WallId(12), End), Outer(WallId(10), Cross), Outer(WallId(11), End),
+                No, Outer(WallId(10), End), No
+            ]
+        );
+    }
+
+    #[test]
+    fn test_priority_dominates() {
+        let size = Size2d::new(2, 2);
+        let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
+        let priority = WallPriority::new(vec![WallId(12), WallId(11)]);
+
+        tilemap.set_border(1, Left, Wall(WallId(13)));
+        tilemap.set_border(2, Back, Wall(WallId(12)));
+        tilemap.set_border(3, Back, Wall(WallId(11)));
+        tilemap.set_border(3, Left, Wall(WallId(11)));
+
+        assert_eq!(
+            calculate_dominant_wall_style(&tilemap, &MostSides, 4),
+            Outer(WallId(11), Cross)
+        );
+        assert_eq!(
+            calculate_dominant_wall_style(&tilemap, &priority, 4),
+            Outer(WallId(12), Cross)
+        );
+    }
+
+    #[test]
+    fn test_priority_falls_back_to_most_sides() {
+        let priority = WallPriority::new(vec![WallId(5)]);
+        let candidates = vec![
+            (WallId(1), vec![Back]),
+            (WallId(2), vec![Left, Right]),
+            (WallId(3), vec![Front]),
+        ];
+
+        assert_eq!(priority.select(&candidates), WallId(2));
+    }
+
+    #[test]
+    fn test_junction_node_styles() {
+        let size = Size2d::new(2, 2);
+        let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
+        let nodes = JunctionNodes::new(NodeId(0), NodeId(1), NodeId(2), NodeId(3));
+        let wall = WallStyle::with_junctions("wall", BoxStyle::shaded(PINK), nodes, 2, None);
+        let node_styles = ["end", "corner", "t", "cross"]
+            .iter()
+            .map(|name| NodeStyle::new(*name, BoxStyle::shaded(PINK), 4))
+            .collect();
+        let styles = StyleMgr::without_manager(
+            vec![],
+            node_styles,
+            vec![],
+            vec![wall],
+            GridStyle::default(),
+        )
+        .unwrap();
+
+        tilemap.set_border(0, Right, Wall(WallId(0)));
+        tilemap.set_border(3, Back, Wall(WallId(0)));
+        tilemap.set_border(3, Left, Wall(WallId(0)));
+
+        let names: Vec<&str> = calculate_node_styles(&styles, &tilemap)
+            .iter()
+            .map(|node| match node {
+                Node::NoNode => "-",
+                Node::InnerNode => "inner",
+                Node::OuterNode(style, _offset) => style.get_name(),
+                Node::Joint(_) => "joint",
+            })
+            .collect();
 
         #[rustfmt::skip]
         assert_eq!(
-            calculate_dominant_wall_styles(&tilemap),
+            names,
+            vec![
+                "-", "end", "-",
+                "-", "t", "end",
+                "-", "end", "-",
+            ]
+        );
+    }
+
+    fn create_joined_styles(join: WallJoin) -> StyleMgr {
+        let thick = WallStyle::new("thick", BoxStyle::shaded(PINK), NodeId(0), 6).joined(join);
+        let thin = WallStyle::new("thin", BoxStyle::shaded(PINK), NodeId(0), 2).joined(join);
+        let node = NodeStyle::new("node", BoxStyle::shaded(PINK), 8);
+
+        StyleMgr::without_manager(
+            vec![],
+            vec![node],
+            vec![],
+            vec![thick, thin],
+            GridStyle::default(),
+        )
+        .unwrap()
+    }
+
+    fn get_joint<'a>(nodes: &'a [Node<'a>], index: usize) -> &'a Joint<'a> {
+        match &nodes[index] {
+            Node::Joint(joint) => joint,
+            node => panic!("Node {} is no joint: {:?}", index, node),
+        }
+    }
+
+    #[test]
+    fn test_filled_corner() {
+        let styles = create_joined_styles(WallJoin::Filled);
+        let mut tilemap = Tilemap2d::default(Size2d::square(1), Empty).unwrap();
+        tilemap.set_border(0, Back, Wall(WallId(0)));
+        tilemap.set_border(0, Left, Wall(WallId(1)));
+
+        let nodes = calculate_node_styles(&styles, &tilemap);
+        let joint = get_joint(&nodes, 0);
+
+        assert_eq!(joint.get_style().get_name(), "thick");
+        assert_eq!(joint.get_width(), 2);
+        assert_eq!(joint.get_depth(), 6);
+        assert_eq!(nodes[0].calculate_extent(Left), 1);
+        assert_eq!(nodes[0].calculate_extent(Right), 1);
+        assert_eq!(nodes[0].calculate_extent(Back), 3);
+        assert_eq!(nodes[0].calculate_extent(Front), 3);
+        assert_eq!(
+            joint.get_parts(),
+            vec![JointPart {
+                style: styles.get_wall_style(WallId(0)),
+                side: None,
+                corners: vec![(-1.0, -3.0), (-1.0, 3.0), (1.0, 3.0), (1.0, -3.0)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_end_of_joined_wall_is_empty() {
+        let styles = create_joined_styles(WallJoin::Filled);
+        let mut tilemap = Tilemap2d::default(Size2d::square(1), Empty).unwrap();
+        tilemap.set_border(0, Back, Wall(WallId(0)));
+
+        let nodes = calculate_node_styles(&styles, &tilemap);
+
+        assert_eq!(nodes[0].calculate_extent(Right), 0);
+        assert!(get_joint(&nodes, 0).get_parts().is_empty());
+    }
+
+    #[test]
+    fn test_mitered_corner() {
+        let styles = create_joined_styles(WallJoin::Mitered);
+        let mut tilemap = Tilemap2d::default(Size2d::square(1), Empty).unwrap();
+        tilemap.set_border(0, Back, Wall(WallId(0)));
+        tilemap.set_border(0, Left, Wall(WallId(1)));
+
+        let nodes = calculate_node_styles(&styles, &tilemap);
+
+        assert_eq!(
+            get_joint(&nodes, 0).get_parts(),
             vec![
-                No, Outer(13), No,
-                Outer(12), Outer(10), Outer(11),
-                No, Outer(10), No
+                JointPart {
+                    style: styles.get_wall_style(WallId(0)),
+                    side: Some(Right),
+                    corners: vec![(1.0, 3.0), (1.0, -3.0), (-1.0, -3.0)],
+                },
+                JointPart {
+                    style: styles.get_wall_style(WallId(1)),
+                    side: Some(Front),
+                    corners: vec![(-1.0, 3.0), (1.0, 3.0), (-1.0, -3.0)],
+                },
             ]
         );
     }
 
+    #[test]
+    fn test_mitered_t_junction_is_filled() {
+        let styles = create_joined_styles(WallJoin::Mitered);
+        let mut tilemap = Tilemap2d::default(Size2d::new(2, 1), Empty).unwrap();
+        tilemap.set_border(0, Back, Wall(WallId(0)));
+        tilemap.set_border(1, Back, Wall(WallId(0)));
+        tilemap.set_border(1, Left, Wall(WallId(1)));
+
+        let nodes = calculate_node_styles(&styles, &tilemap);
+        let parts = get_joint(&nodes, 1).get_parts();
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].get_side(), None);
+    }
+
+    #[test]
+    fn test_aligned_node_moves_with_walls() {
+        let wall = WallStyle::new("wall", BoxStyle::shaded(PINK), NodeId(0), 4)
+            .aligned(WallAlignment::FrontRight);
+        let node = NodeStyle::new("node", BoxStyle::shaded(PINK), 4);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![node], vec![], vec![wall], GridStyle::default())
+                .unwrap();
+        let mut tilemap = Tilemap2d::default(Size2d::square(1), Empty).unwrap();
+        tilemap.set_border(0, Back, Wall(WallId(0)));
+        tilemap.set_border(0, Left, Wall(WallId(0)));
+
+        let nodes = calculate_node_styles(&styles, &tilemap);
+
+        assert_eq!(
+            nodes[0],
+            Node::OuterNode(styles.get_node_style(NodeId(0)), Point2d::new(2, 2))
+        );
+        assert_eq!(nodes[0].calculate_extent(Right), 4);
+        assert_eq!(nodes[0].calculate_extent(Front), 4);
+        // the end of a horizontal wall only moves along the y-axis
+        assert_eq!(
+            nodes[1],
+            Node::OuterNode(styles.get_node_style(NodeId(0)), Point2d::new(0, 2))
+        );
+        assert_eq!(nodes[1].calculate_extent(Left), 2);
+    }
+
+    #[test]
+    fn test_aligned_joint() {
+        let wall = WallStyle::new("wall", BoxStyle::shaded(PINK), NodeId(0), 4)
+            .joined(WallJoin::Filled)
+            .aligned(WallAlignment::BackLeft);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![wall], GridStyle::default())
+                .unwrap();
+        let mut tilemap = Tilemap2d::default(Size2d::square(1), Empty).unwrap();
+        tilemap.set_border(0, Back, Wall(WallId(0)));
+        tilemap.set_border(0, Left, Wall(WallId(0)));
+
+        let nodes = calculate_node_styles(&styles, &tilemap);
+
+        assert_eq!(nodes[0].calculate_extent(Left), 4);
+        assert_eq!(nodes[0].calculate_extent(Right), 0);
+        assert_eq!(nodes[0].calculate_extent(Back), 4);
+        assert_eq!(nodes[0].calculate_extent(Front), 0);
+        assert_eq!(
+            get_joint(&nodes, 0).get_parts()[0].get_corners(),
+            &[(-4.0, -4.0), (-4.0, 0.0), (0.0, 0.0), (0.0, -4.0)]
+        );
+    }
+
     #[test]
     fn test_get_top_styles_empty() {
         assert_eq!(get_top_styles(HashMap::new()), Vec::new());
@@ -230,24 +803,24 @@ mod tests {
     #[test]
     fn test_get_top_styles_one() {
         assert_eq!(
-            get_top_styles(map! {
-            1 => vec![Back, Left],
-            2 => vec![Right],
+            get_top_styles(hash_map! {
+            WallId(1) => vec![Back, Left],
+            WallId(2) => vec![Right],
             }),
-            vec![(1, vec![Back, Left])]
+            vec![(WallId(1), vec![Back, Left])]
         );
     }
 
     #[test]
     fn test_get_top_styles_two() {
-        let top_styles = get_top_styles(map! {
-        1 => vec![Back, Left],
-        2 => vec![Right, Front],
+        let top_styles = get_top_styles(hash_map! {
+        WallId(1) => vec![Back, Left],
+        WallId(2) => vec![Right, Front],
         });
 
         assert_eq!(2, top_styles.len());
-        assert!(top_styles.contains(&(1, vec![Back, Left])));
-        assert!(top_styles.contains(&(2, vec![Right, Front])));
+        assert!(top_styles.contains(&(WallId(1), vec![Back, Left])));
+        assert!(top_styles.contains(&(WallId(2), vec![Right, Front])));
     }
 
     #[test]
@@ -255,17 +828,17 @@ mod tests {
         let size = Size2d::new(3, 3);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(0, Front, Wall(1));
-        tilemap.set_border(0, Right, Wall(1));
-        tilemap.set_border(4, Back, Wall(2));
-        tilemap.set_border(4, Left, Wall(3));
+        tilemap.set_border(0, Front, Wall(WallId(1)));
+        tilemap.set_border(0, Right, Wall(WallId(1)));
+        tilemap.set_border(4, Back, Wall(WallId(2)));
+        tilemap.set_border(4, Left, Wall(WallId(3)));
 
         assert_eq!(
             calculate_sides_per_style(&tilemap, 5),
-            map! {
-            1 => vec![Back, Left],
-            2 => vec![Right],
-            3 => vec![Front],
+            hash_map! {
+            WallId(1) => vec![Back, Left],
+            WallId(2) => vec![Right],
+            WallId(3) => vec![Front],
             }
         );
     }
@@ -0,0 +1,185 @@
+use crate::math::color::Color;
+use crate::math::point2d::Point2d;
+use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
+use crate::port::renderer::Renderer;
+use crate::tilemap::selection::Selection;
+
+/// A [`Renderer`] that finds the element of the tilemap at a specific point of the rendered image.
+///
+/// Because later calls overdraw earlier ones, the last element covering the point is the one visible.
+/// Shadows are ignored, because they only darken the elements below them.
+#[derive(Debug)]
+pub struct Picker {
+    point: Point2d,
+    current: Option<Selection>,
+    selection: Option<Selection>,
+    is_shadow: bool,
+}
+
+impl Picker {
+    pub fn new(point: Point2d) -> Self {
+        Picker {
+            point,
+            current: None,
+            selection: None,
+            is_shadow: false,
+        }
+    }
+
+    /// Returns the visible element at the point, if any.
+    pub fn get_selection(&self) -> Option<Selection> {
+        self.selection
+    }
+
+    fn hit(&mut self) {
+        if self.current.is_some() && !self.is_shadow {
+            self.selection = self.current;
+        }
+    }
+}
+
+impl Renderer for Picker {
+    fn render_rectangle(&mut self, x: i32, y: i32, size: Size2d, _color: Color) {
+        let p = self.point;
+
+        if p.x >= x && p.y >= y && p.x < x + size.width() as i32 && p.y < y + size.height() as i32 {
+            self.hit();
+        }
+    }
+
+    fn render_transformed_rectangle(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        _color: Color,
+    ) {
+        if is_inside_polygon(&[p0, p1, p2, p3], self.point) {
+            self.hit();
+        }
+    }
+
+    fn render_polygon(&mut self, points: &[Point2d], _color: Color) {
+        if points.len() >= 3 && is_inside_polygon(points, self.point) {
+            self.hit();
+        }
+    }
+
+    fn render_line(&mut self, _p0: Point2d, _p1: Point2d, _color: Color) {}
+
+    fn start_selection(&mut self, selection: Selection) {
+        self.current = Some(selection);
+    }
+
+    fn set_layer(&mut self, layer: Layer) {
+        self.is_shadow = layer == Layer::Shadows;
+    }
+}
+
+/// Is the point inside the polygon? Uses the [even-odd rule](https://en.wikipedia.org/wiki/Even%E2%80%93odd_rule).
+/// A polygon with less than 3 points contains nothing.
+///
+/// ```
+///# use tilemap::math::point2d::Point2d;
+///# use tilemap::renderer::picker::is_inside_polygon;
+/// let polygon = [
+///     Point2d::new(0, 0),
+///     Point2d::new(-10, 5),
+///     Point2d::new(0, 10),
+///     Point2d::new(10, 5),
+/// ];
+///
+/// assert!(is_inside_polygon(&polygon, Point2d::new(0, 5)));
+/// assert!(!is_inside_polygon(&polygon, Point2d::new(8, 1)));
+/// assert!(!is_inside_polygon(&[], Point2d::new(0, 5)));
+/// ```
+pub fn is_inside_polygon(polygon: &[Point2d], point: Point2d) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+
+    let x = point.x as f32;
+    let y = point.y as f32;
+    let mut is_inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+
+    for current in polygon {
+        if (current.y as f32 > y) != (previous.y as f32 > y) {
+            let dy = (previous.y - current.y) as f32;
+            let crossing_x =
+                current.x as f32 + (previous.x - current.x) as f32 * (y - current.y as f32) / dy;
+
+            if x < crossing_x {
+                is_inside = !is_inside;
+            }
+        }
+
+        previous = *current;
+    }
+
+    is_inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::PINK;
+
+    #[test]
+    fn test_nothing_rendered() {
+        let picker = Picker::new(Point2d::new(5, 5));
+
+        assert_eq!(picker.get_selection(), None);
+    }
+
+    #[test]
+    fn test_ignore_unselected_calls() {
+        let mut picker = Picker::new(Point2d::new(5, 5));
+
+        picker.render_rectangle(0, 0, Size2d::square(10), PINK);
+
+        assert_eq!(picker.get_selection(), None);
+    }
+
+    #[test]
+    fn test_last_hit_is_visible() {
+        let mut picker = Picker::new(Point2d::new(5, 5));
+
+        picker.start_selection(Selection::Tile(0));
+        picker.render_rectangle(0, 0, Size2d::square(10), PINK);
+        picker.start_selection(Selection::Tile(1));
+        picker.render_rectangle(6, 0, Size2d::square(10), PINK);
+        picker.start_selection(Selection::Node(2));
+        picker.render_rectangle(4, 4, Size2d::square(2), PINK);
+        picker.start_selection(Selection::Tile(3));
+        picker.render_rectangle(0, 6, Size2d::square(10), PINK);
+
+        assert_eq!(picker.get_selection(), Some(Selection::Node(2)));
+    }
+
+    #[test]
+    fn test_ignore_shadows() {
+        let mut picker = Picker::new(Point2d::new(5, 5));
+
+        picker.start_selection(Selection::Tile(0));
+        picker.set_layer(Layer::Floors);
+        picker.render_rectangle(0, 0, Size2d::square(10), PINK);
+        picker.start_selection(Selection::Tile(1));
+        picker.set_layer(Layer::Shadows);
+        picker.render_rectangle(0, 0, Size2d::square(10), PINK);
+
+        assert_eq!(picker.get_selection(), Some(Selection::Tile(0)));
+    }
+
+    #[test]
+    fn test_rectangle_is_half_open() {
+        let mut picker = Picker::new(Point2d::new(10, 10));
+
+        picker.start_selection(Selection::Tile(0));
+        picker.render_rectangle(0, 0, Size2d::square(10), PINK);
+
+        assert_eq!(picker.get_selection(), None);
+    }
+}
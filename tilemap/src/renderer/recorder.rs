@@ -0,0 +1,218 @@
+use crate::math::color::Color;
+use crate::math::point2d::Point2d;
+use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
+use crate::port::renderer::Renderer;
+use crate::renderer::style::aab::Outline;
+use crate::renderer::style::grid::LineStyle;
+use crate::renderer::style::image::Image;
+use crate::renderer::style::pattern::Pattern;
+use crate::tilemap::selection::Selection;
+
+/// A call of the [`Renderer`] trait, that was logged by a [`Recorder`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Call {
+    Rectangle(i32, i32, Size2d, Color),
+    TransformedRectangle([Point2d; 4], Color),
+    Pattern([Point2d; 4], Pattern, Point2d, Color),
+    Image([Point2d; 4], Image, Color),
+    Polygon(Vec<Point2d>, Color),
+    Shadow(Vec<Point2d>, Color, f32),
+    Line(Point2d, Point2d, Color),
+    StyledLine(Point2d, Point2d, LineStyle),
+    Outline(Vec<Point2d>, Outline),
+    Text(Point2d, String, u32, Color),
+    Selection(Selection),
+    Layer(Layer),
+}
+
+/// A [`Renderer`] for tests, that logs all calls instead of rendering them.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    pub calls: Vec<Call>,
+}
+
+impl Recorder {
+    /// Returns the colors of all filled shapes in the order of the calls.
+    pub fn get_colors(&self) -> Vec<Color> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::Rectangle(_, _, _, color)
+                | Call::TransformedRectangle(_, color)
+                | Call::Pattern(_, _, _, color)
+                | Call::Image(_, _, color)
+                | Call::Polygon(_, color) => Some(*color),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the corners of all filled shapes of a selection.
+    pub fn get_points(&self, selection: Selection) -> Vec<Point2d> {
+        let mut current = None;
+        let mut points = Vec::new();
+
+        for call in &self.calls {
+            match call {
+                Call::Selection(s) => current = Some(*s),
+                _ if current != Some(selection) => {}
+                Call::Rectangle(x, y, size, _) => {
+                    let start = Point2d::new(*x, *y);
+                    points.extend([start, start + *size]);
+                }
+                Call::TransformedRectangle(corners, _)
+                | Call::Pattern(corners, _, _, _)
+                | Call::Image(corners, _, _) => points.extend(corners),
+                Call::Polygon(corners, _) => points.extend(corners),
+                _ => {}
+            }
+        }
+
+        points
+    }
+
+    pub fn get_rectangles(&self) -> Vec<(i32, i32, Size2d)> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::Rectangle(x, y, size, _) => Some((*x, *y, *size)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_images(&self) -> Vec<(String, [Point2d; 4])> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::Image(points, image, _) => Some((image.get_path().to_string(), *points)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the styled lines with their width.
+    pub fn get_styled_lines(&self) -> Vec<(Point2d, Point2d, u32)> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::StyledLine(p0, p1, style) => Some((*p0, *p1, style.get_width())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_outlines(&self) -> Vec<Vec<Point2d>> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::Outline(points, _) => Some(points.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_shadows(&self) -> Vec<Vec<Point2d>> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::Shadow(points, _, _) => Some(points.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_texts(&self) -> Vec<(Point2d, String)> {
+        self.calls
+            .iter()
+            .filter_map(|call| match call {
+                Call::Text(center, text, _, _) => Some((*center, text.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Renderer for Recorder {
+    fn render_rectangle(&mut self, x: i32, y: i32, size: Size2d, color: Color) {
+        self.calls.push(Call::Rectangle(x, y, size, color));
+    }
+
+    fn render_transformed_rectangle(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        color: Color,
+    ) {
+        self.calls
+            .push(Call::TransformedRectangle([p0, p1, p2, p3], color));
+    }
+
+    fn render_pattern(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        pattern: &Pattern,
+        tile: Point2d,
+        background: Color,
+    ) {
+        self.calls.push(Call::Pattern(
+            [p0, p1, p2, p3],
+            pattern.clone(),
+            tile,
+            background,
+        ));
+    }
+
+    fn render_image(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        image: &Image,
+        background: Color,
+    ) {
+        self.calls
+            .push(Call::Image([p0, p1, p2, p3], image.clone(), background));
+    }
+
+    fn render_polygon(&mut self, points: &[Point2d], color: Color) {
+        self.calls.push(Call::Polygon(points.to_vec(), color));
+    }
+
+    fn render_shadow(&mut self, points: &[Point2d], color: Color, opacity: f32) {
+        self.calls
+            .push(Call::Shadow(points.to_vec(), color, opacity));
+    }
+
+    fn render_line(&mut self, p0: Point2d, p1: Point2d, color: Color) {
+        self.calls.push(Call::Line(p0, p1, color));
+    }
+
+    fn render_styled_line(&mut self, p0: Point2d, p1: Point2d, style: &LineStyle) {
+        self.calls.push(Call::StyledLine(p0, p1, style.clone()));
+    }
+
+    fn render_outline(&mut self, points: &[Point2d], outline: &Outline) {
+        self.calls.push(Call::Outline(points.to_vec(), *outline));
+    }
+
+    fn render_text(&mut self, center: Point2d, text: &str, size: u32, color: Color) {
+        self.calls
+            .push(Call::Text(center, text.to_string(), size, color));
+    }
+
+    fn start_selection(&mut self, selection: Selection) {
+        self.calls.push(Call::Selection(selection));
+    }
+
+    fn set_layer(&mut self, layer: Layer) {
+        self.calls.push(Call::Layer(layer));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::math::color::Color;
+
+/// A point on the ground in the coordinates of the tilemap, where a tile has the view's tile size.
+pub type GroundPoint = (f32, f32);
+
+/// A directional light casting shadows onto the ground.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sun {
+    direction_x: f32,
+    direction_y: f32,
+    color: Color,
+    /// The opacity of the shadows between 0 & 1.
+    opacity: f32,
+}
+
+impl Sun {
+    /// The direction is the offset of a shadow on the ground per unit of height.
+    /// The shadows have an opacity of 50%.
+    pub fn new(direction_x: f32, direction_y: f32, color: Color) -> Self {
+        Self::with_opacity(direction_x, direction_y, color, 0.5)
+    }
+
+    /// Returns a sun, whose shadows have the desired opacity between 0 & 1.
+    pub fn with_opacity(direction_x: f32, direction_y: f32, color: Color, opacity: f32) -> Self {
+        Sun {
+            direction_x,
+            direction_y,
+            color,
+            opacity: opacity.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn get_direction(&self) -> GroundPoint {
+        (self.direction_x, self.direction_y)
+    }
+
+    /// Returns the color of the shadows.
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    /// Returns the opacity of the shadows between 0 & 1.
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Calculates the shadow of an axis aligned box on the ground.
+    ///
+    /// Returns the quads covering the footprint shifted by the sun & the area swept by its 2 leading edges.
+    /// The footprint itself is left out, because the box hides it.
+    ///
+    /// ```
+    ///# use tilemap::math::color::BLACK;
+    ///# use tilemap::renderer::shadow::Sun;
+    /// let sun = Sun::new(0.5, 0.0, BLACK);
+    /// let quads = sun.calculate_box_shadow((0.0, 0.0), (10.0, 10.0), 10.0);
+    ///
+    /// assert_eq!(quads[0], [(5.0, 0.0), (5.0, 10.0), (15.0, 10.0), (15.0, 0.0)]);
+    /// ```
+    pub fn calculate_box_shadow(
+        &self,
+        start: GroundPoint,
+        size: GroundPoint,
+        height: f32,
+    ) -> Vec<[GroundPoint; 4]> {
+        let dx = self.direction_x * height;
+        let dy = self.direction_y * height;
+        let (x0, y0) = start;
+        let (x1, y1) = (x0 + size.0, y0 + size.1);
+        let edge_x = if dx > 0.0 { x1 } else { x0 };
+        let edge_y = if dy > 0.0 { y1 } else { y0 };
+
+        vec![
+            [
+                (x0 + dx, y0 + dy),
+                (x0 + dx, y1 + dy),
+                (x1 + dx, y1 + dy),
+                (x1 + dx, y0 + dy),
+            ],
+            [
+                (edge_x, y0),
+                (edge_x, y1),
+                (edge_x + dx, y1 + dy),
+                (edge_x + dx, y0 + dy),
+            ],
+            [
+                (x0, edge_y),
+                (x1, edge_y),
+                (x1 + dx, edge_y + dy),
+                (x0 + dx, edge_y + dy),
+            ],
+        ]
+    }
+
+    /// Calculates the shadow of a prism on the ground, whose footprint is a convex polygon.
+    /// Its corners are ordered like for [`NodeShape::get_corners()`](crate::renderer::style::node::NodeShape::get_corners).
+    ///
+    /// Returns the footprint shifted by the sun & a quad for each edge facing away from the sun,
+    /// that covers the area swept by it.
+    ///
+    /// ```
+    ///# use tilemap::math::color::BLACK;
+    ///# use tilemap::renderer::shadow::Sun;
+    /// let sun = Sun::new(0.5, 0.0, BLACK);
+    /// let triangle = [(0.0, 0.0), (0.0, 10.0), (10.0, 5.0)];
+    /// let polygons = sun.calculate_prism_shadow(&triangle, 10.0);
+    ///
+    /// assert_eq!(polygons.len(), 3);
+    /// assert_eq!(polygons[0], vec![(5.0, 0.0), (5.0, 10.0), (15.0, 5.0)]);
+    /// ```
+    pub fn calculate_prism_shadow(
+        &self,
+        corners: &[GroundPoint],
+        height: f32,
+    ) -> Vec<Vec<GroundPoint>> {
+        let dx = self.direction_x * height;
+        let dy = self.direction_y * height;
+        let mut polygons = vec![corners.iter().map(|(x, y)| (x + dx, y + dy)).collect()];
+
+        for (index, start) in corners.iter().enumerate() {
+            let end = corners[(index + 1) % corners.len()];
+            let normal = (start.1 - end.1, end.0 - start.0);
+
+            if normal.0 * dx + normal.1 * dy > 0.0 {
+                polygons.push(vec![
+                    *start,
+                    end,
+                    (end.0 + dx, end.1 + dy),
+                    (start.0 + dx, start.1 + dy),
+                ]);
+            }
+        }
+
+        polygons
+    }
+}
+
+/// Clips a convex polygon to an axis aligned rectangle from start to end.
+/// Returns no points, if nothing with an area remains.
+///
+/// ```
+///# use tilemap::renderer::shadow::clip_polygon;
+/// let triangle = [(0.0, 0.0), (0.0, 10.0), (10.0, 0.0)];
+///
+/// assert_eq!(
+///     clip_polygon(&triangle, (0.0, 0.0), (5.0, 5.0)),
+///     vec![(0.0, 0.0), (0.0, 5.0), (5.0, 5.0), (5.0, 0.0)]
+/// );
+/// assert!(clip_polygon(&triangle, (6.0, 6.0), (9.0, 9.0)).is_empty());
+/// ```
+pub fn clip_polygon(
+    polygon: &[GroundPoint],
+    start: GroundPoint,
+    end: GroundPoint,
+) -> Vec<GroundPoint> {
+    let mut points = polygon.to_vec();
+
+    points = clip_edge(&points, |p| p.0 - start.0, |a, b| lerp_x(a, b, start.0));
+    points = clip_edge(&points, |p| end.0 - p.0, |a, b| lerp_x(a, b, end.0));
+    points = clip_edge(&points, |p| p.1 - start.1, |a, b| lerp_y(a, b, start.1));
+    points = clip_edge(&points, |p| end.1 - p.1, |a, b| lerp_y(a, b, end.1));
+
+    points.dedup();
+
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    if calculate_area(&points) <= f32::EPSILON {
+        return Vec::new();
+    }
+
+    points
+}
+
+/// Calculates the area of a polygon with the shoelace formula.
+fn calculate_area(polygon: &[GroundPoint]) -> f32 {
+    let sum: f32 = polygon
+        .iter()
+        .enumerate()
+        .map(|(index, start)| {
+            let end = polygon[(index + 1) % polygon.len()];
+            start.0 * end.1 - end.0 * start.1
+        })
+        .sum();
+
+    sum.abs() / 2.0
+}
+
+/// Keeps the part of a polygon, where the distance to an edge is positive.
+fn clip_edge(
+    polygon: &[GroundPoint],
+    distance: impl Fn(GroundPoint) -> f32,
+    intersect: impl Fn(GroundPoint, GroundPoint) -> GroundPoint,
+) -> Vec<GroundPoint> {
+    let mut result = Vec::new();
+
+    for (index, start) in polygon.iter().enumerate() {
+        let end = polygon[(index + 1) % polygon.len()];
+        let is_start_inside = distance(*start) >= 0.0;
+        let is_end_inside = distance(end) >= 0.0;
+
+        if is_start_inside {
+            result.push(*start);
+        }
+
+        if is_start_inside != is_end_inside {
+            result.push(intersect(*start, end));
+        }
+    }
+
+    result
+}
+
+fn lerp_x(a: GroundPoint, b: GroundPoint, x: f32) -> GroundPoint {
+    (x, a.1 + (b.1 - a.1) * (x - a.0) / (b.0 - a.0))
+}
+
+fn lerp_y(a: GroundPoint, b: GroundPoint, y: f32) -> GroundPoint {
+    (a.0 + (b.0 - a.0) * (y - a.1) / (b.1 - a.1), y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::BLACK;
+
+    #[test]
+    fn test_clip_polygon_touching_rectangle() {
+        let quad = [(10.0, 0.0), (10.0, 10.0), (20.0, 10.0), (20.0, 0.0)];
+
+        assert!(clip_polygon(&quad, (0.0, 0.0), (10.0, 10.0)).is_empty());
+    }
+
+    #[test]
+    fn test_shadow_towards_back_left() {
+        let sun = Sun::new(-0.5, -0.25, BLACK);
+
+        assert_eq!(
+            sun.calculate_box_shadow((10.0, 20.0), (4.0, 8.0), 8.0),
+            vec![
+                [(6.0, 18.0), (6.0, 26.0), (10.0, 26.0), (10.0, 18.0)],
+                [(10.0, 20.0), (10.0, 28.0), (6.0, 26.0), (6.0, 18.0)],
+                [(10.0, 20.0), (14.0, 20.0), (10.0, 18.0), (6.0, 18.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shadow_towards_front_right() {
+        let sun = Sun::new(1.0, 1.0, BLACK);
+
+        assert_eq!(
+            sun.calculate_box_shadow((0.0, 0.0), (2.0, 2.0), 1.0),
+            vec![
+                [(1.0, 1.0), (1.0, 3.0), (3.0, 3.0), (3.0, 1.0)],
+                [(2.0, 0.0), (2.0, 2.0), (3.0, 3.0), (3.0, 1.0)],
+                [(0.0, 2.0), (2.0, 2.0), (3.0, 3.0), (1.0, 3.0)],
+            ]
+        );
+    }
+}
@@ -1,27 +1,168 @@
 use crate::math::color::Color;
+use crate::math::side::Side;
+use crate::renderer::style::image::Image;
+
+/// The lines drawn along the edges of each visible face of a box.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Outline {
+    color: Color,
+    width: u32,
+}
+
+impl Outline {
+    pub fn new(color: Color, width: u32) -> Self {
+        Outline { color, width }
+    }
+
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+}
 
 /// Defines how to render an axis aligned box. Used by other styles.
+///
+/// The front & side faces point to the front & right of the tilemap.
+/// The back & left faces are only visible from a rotated camera.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BoxStyle {
     front: Color,
     side: Color,
     top: Color,
+    back: Color,
+    left: Color,
+    outline: Option<Outline>,
+    top_image: Option<Image>,
+    side_image: Option<Image>,
 }
 
 impl BoxStyle {
+    /// Creates a style, where the back & left faces have the colors of the front & side faces.
     pub fn new(front: Color, side: Color, top: Color) -> Self {
-        BoxStyle { front, side, top }
+        Self::with_hidden_faces(front, side, top, front, side)
+    }
+
+    /// Creates a style with an extra color for the back & left faces.
+    pub fn with_hidden_faces(
+        front: Color,
+        side: Color,
+        top: Color,
+        back: Color,
+        left: Color,
+    ) -> Self {
+        BoxStyle {
+            front,
+            side,
+            top,
+            back,
+            left,
+            outline: None,
+            top_image: None,
+            side_image: None,
+        }
+    }
+
+    /// Creates a style, that outlines the edges of the faces.
+    pub fn with_outline(front: Color, side: Color, top: Color, outline: Outline) -> Self {
+        Self::new(front, side, top).outlined(outline)
     }
 
     /// Fakes lighting by darkening the front & side faces.
     pub fn shaded(color: Color) -> Self {
+        Self::new(color * 0.8, color * 0.6, color)
+    }
+
+    /// Returns a copy with other colors, but the same outline & images.
+    ///
+    /// ```
+    ///# use tilemap::math::color::{BLACK, WHITE};
+    ///# use tilemap::renderer::style::aab::{BoxStyle, Outline};
+    /// let outline = Outline::new(BLACK, 2);
+    /// let style = BoxStyle::with_outline(WHITE, WHITE, WHITE, outline);
+    ///
+    /// assert_eq!(
+    ///     style.recolor(BLACK, BLACK, WHITE),
+    ///     BoxStyle::with_outline(BLACK, BLACK, WHITE, outline)
+    /// );
+    /// ```
+    pub fn recolor(&self, front: Color, side: Color, top: Color) -> Self {
+        BoxStyle {
+            outline: self.outline,
+            top_image: self.top_image.clone(),
+            side_image: self.side_image.clone(),
+            ..Self::new(front, side, top)
+        }
+    }
+
+    /// Returns a copy with an outline.
+    pub fn outlined(&self, outline: Outline) -> Self {
+        BoxStyle {
+            outline: Some(outline),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy with the colors of all faces multiplied by a factor, e.g. to vary the brightness.
+    ///
+    /// ```
+    ///# use tilemap::math::color::Color;
+    ///# use tilemap::renderer::style::aab::BoxStyle;
+    /// let style = BoxStyle::new(Color::gray(100), Color::gray(50), Color::gray(200));
+    ///
+    /// assert_eq!(
+    ///     style.scaled(0.5),
+    ///     BoxStyle::new(Color::gray(50), Color::gray(25), Color::gray(100))
+    /// );
+    /// ```
+    pub fn scaled(&self, factor: f32) -> Self {
         BoxStyle {
-            front: color * 0.8,
-            side: color * 0.6,
-            top: color,
+            front: self.front * factor,
+            side: self.side * factor,
+            top: self.top * factor,
+            back: self.back * factor,
+            left: self.left * factor,
+            ..self.clone()
         }
     }
 
+    /// Returns a copy with an [`Image`] for the top face and one for all vertical faces.
+    /// The colors are still used by renderers without images & for faces, that aren't rectangles.
+    ///
+    /// ```
+    ///# use tilemap::math::color::RED;
+    ///# use tilemap::renderer::style::aab::BoxStyle;
+    ///# use tilemap::renderer::style::image::Image;
+    /// let style = BoxStyle::shaded(RED).textured(Some(Image::new("roof.png")), None);
+    ///
+    /// assert_eq!(style.get_top_image(), Some(&Image::new("roof.png")));
+    /// assert_eq!(style.get_side_image(), None);
+    /// ```
+    pub fn textured(&self, top: Option<Image>, sides: Option<Image>) -> Self {
+        BoxStyle {
+            top_image: top,
+            side_image: sides,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy, where the front & side colors are the ones of the faces pointing to specific sides.
+    /// Used to render the box with a rotated camera.
+    ///
+    /// ```
+    ///# use tilemap::math::color::{BLACK, BLUE, GREEN, RED, WHITE};
+    ///# use tilemap::math::side::Side;
+    ///# use tilemap::renderer::style::aab::BoxStyle;
+    /// let style = BoxStyle::with_hidden_faces(RED, GREEN, WHITE, BLUE, BLACK);
+    ///
+    /// assert_eq!(style.facing(Side::Left, Side::Back), BoxStyle::new(BLACK, BLUE, WHITE));
+    /// ```
+    pub fn facing(&self, front: Side, side: Side) -> Self {
+        self.recolor(*self.get_color(front), *self.get_color(side), self.top)
+    }
+
     pub fn get_front_color(&self) -> &Color {
         &self.front
     }
@@ -33,4 +174,35 @@ impl BoxStyle {
     pub fn get_top_color(&self) -> &Color {
         &self.top
     }
+
+    pub fn get_back_color(&self) -> &Color {
+        &self.back
+    }
+
+    pub fn get_left_color(&self) -> &Color {
+        &self.left
+    }
+
+    /// Returns the color of the vertical face pointing to a specific side.
+    pub fn get_color(&self, side: Side) -> &Color {
+        match side {
+            Side::Back => &self.back,
+            Side::Left => &self.left,
+            Side::Front => &self.front,
+            Side::Right => &self.side,
+        }
+    }
+
+    pub fn get_outline(&self) -> Option<&Outline> {
+        self.outline.as_ref()
+    }
+
+    pub fn get_top_image(&self) -> Option<&Image> {
+        self.top_image.as_ref()
+    }
+
+    /// Returns the image of the vertical faces.
+    pub fn get_side_image(&self) -> Option<&Image> {
+        self.side_image.as_ref()
+    }
 }
@@ -1,4 +1,7 @@
 use crate::math::color::{Color, PINK};
+use crate::renderer::style::image::Image;
+use crate::renderer::style::jitter::ColorJitter;
+use crate::renderer::style::pattern::Pattern;
 use crate::utils::resource::Resource;
 
 /// Defines how to render a [`floor tile`](crate::tilemap::tile::Tile::Floor).
@@ -6,6 +9,9 @@ use crate::utils::resource::Resource;
 pub struct FloorStyle {
     name: String,
     floor: Color,
+    pattern: Option<Pattern>,
+    image: Option<Image>,
+    jitter: Option<ColorJitter>,
 }
 
 impl FloorStyle {
@@ -13,12 +19,80 @@ impl FloorStyle {
         FloorStyle {
             name: name.into(),
             floor,
+            pattern: None,
+            image: None,
+            jitter: None,
+        }
+    }
+
+    /// Returns a style with a [`Pattern`] on top of the floor color.
+    pub fn with_pattern<S: Into<String>>(name: S, floor: Color, pattern: Pattern) -> Self {
+        FloorStyle {
+            name: name.into(),
+            floor,
+            pattern: Some(pattern),
+            image: None,
+            jitter: None,
+        }
+    }
+
+    /// Returns a style with an [`Image`] instead of the floor color.
+    /// The floor color is still used by renderers without images.
+    pub fn with_image<S: Into<String>>(name: S, floor: Color, image: Image) -> Self {
+        FloorStyle {
+            name: name.into(),
+            floor,
+            pattern: None,
+            image: Some(image),
+            jitter: None,
+        }
+    }
+
+    /// Returns a copy, whose floor color varies per tile.
+    pub fn jittered(&self, jitter: ColorJitter) -> Self {
+        FloorStyle {
+            jitter: Some(jitter),
+            ..self.clone()
         }
     }
 
     pub fn get_floor_color(&self) -> &Color {
         &self.floor
     }
+
+    /// Returns the floor color of a specific tile, which differs from the floor color with a jitter.
+    ///
+    /// ```
+    ///# use tilemap::math::color::Color;
+    ///# use tilemap::renderer::style::floor::FloorStyle;
+    ///# use tilemap::renderer::style::jitter::ColorJitter;
+    /// let style = FloorStyle::new("stone", Color::gray(100));
+    /// let jitter = ColorJitter::new(3, 10);
+    ///
+    /// assert_eq!(style.get_tile_color(2, 5), Color::gray(100));
+    /// assert_eq!(
+    ///     style.jittered(jitter).get_tile_color(2, 5),
+    ///     jitter.apply(Color::gray(100), 2, 5)
+    /// );
+    /// ```
+    pub fn get_tile_color(&self, x: u32, y: u32) -> Color {
+        match &self.jitter {
+            None => self.floor,
+            Some(jitter) => jitter.apply(self.floor, x, y),
+        }
+    }
+
+    pub fn get_jitter(&self) -> Option<&ColorJitter> {
+        self.jitter.as_ref()
+    }
+
+    pub fn get_pattern(&self) -> Option<&Pattern> {
+        self.pattern.as_ref()
+    }
+
+    pub fn get_image(&self) -> Option<&Image> {
+        self.image.as_ref()
+    }
 }
 
 impl Default for FloorStyle {
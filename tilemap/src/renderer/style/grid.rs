@@ -0,0 +1,208 @@
+use crate::math::color::{Color, BLACK};
+use crate::utils::resource::Resource;
+
+/// Defines how to render a line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineStyle {
+    color: Color,
+    width: u32,
+    /// The alternating lengths of dashes & gaps. The line is solid, if empty.
+    dash: Vec<u32>,
+    /// From 0 (transparent) to 1 (opaque).
+    opacity: f32,
+}
+
+impl LineStyle {
+    pub fn new(color: Color, width: u32, dash: Vec<u32>, opacity: f32) -> Self {
+        LineStyle {
+            color,
+            width,
+            dash,
+            opacity: opacity.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Returns an opaque solid line.
+    pub fn solid(color: Color, width: u32) -> Self {
+        Self::new(color, width, Vec::new(), 1.0)
+    }
+
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_dash(&self) -> &[u32] {
+        &self.dash
+    }
+
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+}
+
+/// How the rows & columns of the grid are labeled.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LabelFormat {
+    /// Letters for columns & numbers starting at 1 for rows, like A1 on a chessboard.
+    Chess,
+    /// Numbers starting at 0 for both, like the coordinates of a tile.
+    Numeric,
+}
+
+impl LabelFormat {
+    /// Returns the label of a column.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::grid::LabelFormat;
+    /// assert_eq!(LabelFormat::Chess.get_column_label(0), "A");
+    /// assert_eq!(LabelFormat::Chess.get_column_label(25), "Z");
+    /// assert_eq!(LabelFormat::Chess.get_column_label(26), "AA");
+    /// assert_eq!(LabelFormat::Numeric.get_column_label(26), "26");
+    /// ```
+    pub fn get_column_label(&self, column: u32) -> String {
+        match self {
+            LabelFormat::Chess => {
+                let mut label = Vec::new();
+                let mut rest = column + 1;
+
+                while rest > 0 {
+                    rest -= 1;
+                    label.push((b'A' + (rest % 26) as u8) as char);
+                    rest /= 26;
+                }
+
+                label.iter().rev().collect()
+            }
+            LabelFormat::Numeric => column.to_string(),
+        }
+    }
+
+    /// Returns the label of a row.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::grid::LabelFormat;
+    /// assert_eq!(LabelFormat::Chess.get_row_label(0), "1");
+    /// assert_eq!(LabelFormat::Numeric.get_row_label(0), "0");
+    /// ```
+    pub fn get_row_label(&self, row: u32) -> String {
+        match self {
+            LabelFormat::Chess => (row + 1).to_string(),
+            LabelFormat::Numeric => row.to_string(),
+        }
+    }
+}
+
+/// When the grid is rendered by [`View::render()`](crate::renderer::view::View::render).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GridPosition {
+    /// The grid is only rendered by [`View::render_grid()`](crate::renderer::view::View::render_grid).
+    Hidden,
+    /// Rendered after the floors, so solids, walls & nodes hide it.
+    /// Renderers grouping by [`layer`](crate::port::layer::Layer) still stack it above the walls.
+    BelowWalls,
+    /// Rendered on top of everything else.
+    AboveWalls,
+}
+
+/// Defines how to render the grid between the tiles.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridStyle {
+    name: String,
+    line: LineStyle,
+    /// The optional line around the whole tilemap.
+    frame: Option<LineStyle>,
+    /// The optional labels of the rows & columns at the back & left edge.
+    /// They are rendered in the label margin of the view or inside the tiles at the edge without one.
+    labels: Option<LabelFormat>,
+    position: GridPosition,
+}
+
+impl GridStyle {
+    pub fn new<S: Into<String>>(
+        name: S,
+        line: LineStyle,
+        frame: Option<LineStyle>,
+        labels: Option<LabelFormat>,
+        position: GridPosition,
+    ) -> Self {
+        GridStyle {
+            name: name.into(),
+            line,
+            frame,
+            labels,
+            position,
+        }
+    }
+
+    /// Returns a grid of thin dotted lines on top of everything else.
+    pub fn dotted<S: Into<String>>(name: S, color: Color) -> Self {
+        Self::new(
+            name,
+            LineStyle::new(color, 1, vec![1, 4], 1.0),
+            None,
+            None,
+            GridPosition::AboveWalls,
+        )
+    }
+
+    pub fn get_line_style(&self) -> &LineStyle {
+        &self.line
+    }
+
+    pub fn get_frame_style(&self) -> Option<&LineStyle> {
+        self.frame.as_ref()
+    }
+
+    pub fn get_label_format(&self) -> Option<LabelFormat> {
+        self.labels
+    }
+
+    pub fn get_position(&self) -> GridPosition {
+        self.position
+    }
+}
+
+/// Returns a hidden grid of thin dotted lines, that is only rendered by
+/// [`View::render_grid()`](crate::renderer::view::View::render_grid).
+impl Default for GridStyle {
+    fn default() -> Self {
+        Self {
+            position: GridPosition::Hidden,
+            ..Self::dotted("default", BLACK)
+        }
+    }
+}
+
+impl Resource for GridStyle {
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chess_column_labels() {
+        let labels: Vec<String> = [0, 1, 25, 26, 27, 51, 52, 701, 702]
+            .iter()
+            .map(|column| LabelFormat::Chess.get_column_label(*column))
+            .collect();
+
+        assert_eq!(
+            labels,
+            vec!["A", "B", "Z", "AA", "AB", "AZ", "BA", "ZZ", "AAA"]
+        );
+    }
+
+    #[test]
+    fn test_opacity_is_clamped() {
+        assert_eq!(LineStyle::new(BLACK, 1, vec![], 1.5).get_opacity(), 1.0);
+        assert_eq!(LineStyle::new(BLACK, 1, vec![], -0.5).get_opacity(), 0.0);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::math::size2d::Size2d;
+
+/// A rectangular region of an image in pixels, e.g. a sprite of a sprite sheet.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ImageRegion {
+    x: u32,
+    y: u32,
+    size: Size2d,
+}
+
+impl ImageRegion {
+    pub fn new(x: u32, y: u32, size: Size2d) -> Self {
+        ImageRegion { x, y, size }
+    }
+
+    pub fn get_x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn get_y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn get_size(&self) -> Size2d {
+        self.size
+    }
+}
+
+/// An artist-drawn image, that replaces the color of a face or tile.
+///
+/// The image or a region of it is stretched across the face,
+/// so it follows the perspective of the view like a [`Pattern`](crate::renderer::style::pattern::Pattern).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    path: String,
+    size: Option<Size2d>,
+    region: Option<ImageRegion>,
+}
+
+impl Image {
+    /// Returns the whole image of a file.
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        Image {
+            path: path.into(),
+            size: None,
+            region: None,
+        }
+    }
+
+    /// Returns a sprite of a sprite sheet with a specific size in pixels.
+    ///
+    /// ```
+    ///# use tilemap::math::size2d::Size2d;
+    ///# use tilemap::renderer::style::image::{Image, ImageRegion};
+    /// let region = ImageRegion::new(32, 0, Size2d::square(16));
+    /// let image = Image::sprite("sheet.png", Size2d::new(64, 32), region);
+    ///
+    /// assert_eq!(image.get_path(), "sheet.png");
+    /// assert_eq!(image.get_size(), Some(Size2d::new(64, 32)));
+    /// assert_eq!(image.get_region().map(|r| r.get_x()), Some(32));
+    /// ```
+    pub fn sprite<S: Into<String>>(path: S, size: Size2d, region: ImageRegion) -> Self {
+        Image {
+            path: path.into(),
+            size: Some(size),
+            region: Some(region),
+        }
+    }
+
+    /// Returns the path or url of the image file.
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the size of the image file in pixels, if it is known, e.g. for a sprite sheet.
+    pub fn get_size(&self) -> Option<Size2d> {
+        self.size
+    }
+
+    /// Returns the region of the image file, if only a part of it is used.
+    pub fn get_region(&self) -> Option<&ImageRegion> {
+        self.region.as_ref()
+    }
+}
@@ -0,0 +1,70 @@
+use crate::math::color::Color;
+use crate::utils::hash::hash2d_unit;
+
+/// A deterministic variation of the brightness per tile, so large areas of one style look less uniform.
+///
+/// The variation only depends on the seed & the position of the tile,
+/// so rendering the same tilemap twice gives the same result.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ColorJitter {
+    seed: u32,
+    strength: u32,
+}
+
+impl ColorJitter {
+    /// The strength is the maximal change of the brightness in percent.
+    pub fn new(seed: u32, strength: u32) -> Self {
+        ColorJitter { seed, strength }
+    }
+
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn get_strength(&self) -> u32 {
+        self.strength
+    }
+
+    /// Returns the factor of the brightness of a tile.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::jitter::ColorJitter;
+    /// let jitter = ColorJitter::new(42, 10);
+    ///
+    /// for x in 0..100 {
+    ///     let factor = jitter.get_factor(x, 3);
+    ///     assert!(factor >= 0.9 && factor <= 1.1);
+    /// }
+    /// ```
+    pub fn get_factor(&self, x: u32, y: u32) -> f32 {
+        let offset = 2.0 * hash2d_unit(x, y, self.seed) - 1.0;
+        1.0 + offset * self.strength as f32 / 100.0
+    }
+
+    /// Returns the color of a tile.
+    pub fn apply(&self, color: Color, x: u32, y: u32) -> Color {
+        color * self.get_factor(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let jitter = ColorJitter::new(7, 20);
+        let color = Color::gray(100);
+
+        assert_eq!(jitter.apply(color, 3, 4), jitter.apply(color, 3, 4));
+        assert_ne!(jitter.apply(color, 3, 4), jitter.apply(color, 4, 3));
+    }
+
+    #[test]
+    fn test_without_strength() {
+        let jitter = ColorJitter::new(7, 0);
+        let color = Color::from_rgb(10, 100, 200);
+
+        assert_eq!(jitter.apply(color, 3, 4), color);
+    }
+}
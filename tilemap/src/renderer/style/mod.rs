@@ -1,5 +1,6 @@
-use crate::math::color::Color;
+use crate::renderer::node::{MostSides, NodeDominance};
 use crate::renderer::style::floor::FloorStyle;
+use crate::renderer::style::grid::GridStyle;
 use crate::renderer::style::node::NodeStyle;
 use crate::renderer::style::solid::SolidStyle;
 use crate::renderer::style::wall::WallStyle;
@@ -7,30 +8,39 @@ use crate::tilemap::border::WallId;
 use crate::tilemap::tile::{FloorId, SolidId};
 use crate::tilemap::NodeId;
 use crate::utils::resource::ResourceManager;
+use anyhow::{Context, Result};
 
 pub mod aab;
 pub mod floor;
+pub mod grid;
+pub mod image;
+pub mod jitter;
 pub mod node;
+pub mod pattern;
+pub mod shading;
+#[cfg(feature = "sheet")]
+pub mod sheet;
 pub mod solid;
 pub mod wall;
 
 #[derive(Debug)]
 pub struct StyleMgr {
-    floors: ResourceManager<FloorStyle>,
-    nodes: ResourceManager<NodeStyle>,
-    solids: ResourceManager<SolidStyle>,
-    walls: ResourceManager<WallStyle>,
-    grid: Color,
+    floors: ResourceManager<FloorId, FloorStyle>,
+    nodes: ResourceManager<NodeId, NodeStyle>,
+    solids: ResourceManager<SolidId, SolidStyle>,
+    walls: ResourceManager<WallId, WallStyle>,
+    grid: GridStyle,
+    dominance: Box<dyn NodeDominance>,
 }
 
 impl StyleMgr {
     /// Many styles per type.
     pub fn new(
-        floors: ResourceManager<FloorStyle>,
-        nodes: ResourceManager<NodeStyle>,
-        solids: ResourceManager<SolidStyle>,
-        walls: ResourceManager<WallStyle>,
-        grid: Color,
+        floors: ResourceManager<FloorId, FloorStyle>,
+        nodes: ResourceManager<NodeId, NodeStyle>,
+        solids: ResourceManager<SolidId, SolidStyle>,
+        walls: ResourceManager<WallId, WallStyle>,
+        grid: GridStyle,
     ) -> Self {
         StyleMgr {
             floors,
@@ -38,23 +48,25 @@ impl StyleMgr {
             solids,
             walls,
             grid,
+            dominance: Box::new(MostSides),
         }
     }
 
+    /// Creates the styles from lists, if the names of each type of style are unique.
     pub fn without_manager(
         floors: Vec<FloorStyle>,
         nodes: Vec<NodeStyle>,
         solids: Vec<SolidStyle>,
         walls: Vec<WallStyle>,
-        grid: Color,
-    ) -> Self {
-        Self::new(
-            ResourceManager::with_default(floors),
-            ResourceManager::with_default(nodes),
-            ResourceManager::with_default(solids),
-            ResourceManager::with_default(walls),
+        grid: GridStyle,
+    ) -> Result<Self> {
+        Ok(Self::new(
+            ResourceManager::with_default(floors).context("Invalid floor styles")?,
+            ResourceManager::with_default(nodes).context("Invalid node styles")?,
+            ResourceManager::with_default(solids).context("Invalid solid styles")?,
+            ResourceManager::with_default(walls).context("Invalid wall styles")?,
             grid,
-        )
+        ))
     }
 
     /// Only one style per type.
@@ -63,13 +75,13 @@ impl StyleMgr {
         node: NodeStyle,
         solid: SolidStyle,
         wall: WallStyle,
-        grid: Color,
+        grid: GridStyle,
     ) -> Self {
         Self::new(
-            ResourceManager::new(Vec::new(), floor),
-            ResourceManager::new(Vec::new(), node),
-            ResourceManager::new(Vec::new(), solid),
-            ResourceManager::new(Vec::new(), wall),
+            ResourceManager::empty(floor),
+            ResourceManager::empty(node),
+            ResourceManager::empty(solid),
+            ResourceManager::empty(wall),
             grid,
         )
     }
@@ -78,27 +90,74 @@ impl StyleMgr {
         self.floors.get(id)
     }
 
+    pub fn get_floor_styles(&self) -> &ResourceManager<FloorId, FloorStyle> {
+        &self.floors
+    }
+
+    /// Allows to edit the floor styles without invalidating the [`FloorId`]s of a tilemap.
+    pub fn get_floor_styles_mut(&mut self) -> &mut ResourceManager<FloorId, FloorStyle> {
+        &mut self.floors
+    }
+
     pub fn get_node_style(&self, id: NodeId) -> &NodeStyle {
         self.nodes.get(id)
     }
 
-    pub fn get_node_styles(&self) -> &ResourceManager<NodeStyle> {
+    pub fn get_node_styles(&self) -> &ResourceManager<NodeId, NodeStyle> {
         &self.nodes
     }
 
+    pub fn get_node_styles_mut(&mut self) -> &mut ResourceManager<NodeId, NodeStyle> {
+        &mut self.nodes
+    }
+
     pub fn get_solid_style(&self, id: SolidId) -> &SolidStyle {
         self.solids.get(id)
     }
 
+    pub fn get_solid_styles(&self) -> &ResourceManager<SolidId, SolidStyle> {
+        &self.solids
+    }
+
+    pub fn get_solid_styles_mut(&mut self) -> &mut ResourceManager<SolidId, SolidStyle> {
+        &mut self.solids
+    }
+
     pub fn get_wall_style(&self, id: WallId) -> &WallStyle {
         self.walls.get(id)
     }
 
-    pub fn get_wall_styles(&self) -> &ResourceManager<WallStyle> {
+    pub fn get_wall_styles(&self) -> &ResourceManager<WallId, WallStyle> {
         &self.walls
     }
 
-    pub fn get_grid_color(&self) -> &Color {
+    pub fn get_wall_styles_mut(&mut self) -> &mut ResourceManager<WallId, WallStyle> {
+        &mut self.walls
+    }
+
+    pub fn get_grid_style(&self) -> &GridStyle {
         &self.grid
     }
+
+    pub fn get_node_dominance(&self) -> &dyn NodeDominance {
+        self.dominance.as_ref()
+    }
+
+    /// Changes, which wall style determines a node, where several wall styles meet.
+    pub fn set_node_dominance(&mut self, dominance: Box<dyn NodeDominance>) {
+        self.dominance = dominance;
+    }
+
+    /// Returns the largest height of all solid, node & wall styles with their own height.
+    pub fn get_max_height(&self) -> Option<u32> {
+        let solids = self.solids.iter().filter_map(|style| style.get_height());
+        let nodes = self.nodes.iter().filter_map(|style| style.get_height());
+        let walls = self.walls.iter().filter_map(|style| style.get_height());
+
+        solids.chain(nodes).chain(walls).max()
+    }
+
+    pub fn set_grid_style(&mut self, grid: GridStyle) {
+        self.grid = grid;
+    }
 }
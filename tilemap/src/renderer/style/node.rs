@@ -1,23 +1,113 @@
 use crate::math::color::PINK;
 use crate::renderer::style::aab::BoxStyle;
 use crate::utils::resource::Resource;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_8, PI, SQRT_2, TAU};
+
+/// The number of corners approximating a [`NodeShape::Circle`].
+const CIRCLE_CORNERS: u32 = 32;
+
+/// The shape of a node seen from above, which fits into a square of the node's size.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum NodeShape {
+    /// An axis aligned square.
+    #[default]
+    Square,
+    /// A circle, like a round tower.
+    Circle,
+    /// A regular octagon, whose sides point along the axes & diagonals.
+    Octagon,
+    /// A regular polygon with at least 3 corners, where the first one points to the back.
+    Polygon(u32),
+}
+
+impl NodeShape {
+    /// Returns the corners of the shape for a size, relative to its center.
+    /// They are ordered counter-clockwise in the image, like the points of a rectangle.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::node::NodeShape;
+    /// let corners: Vec<(i32, i32)> = NodeShape::Polygon(4)
+    ///     .get_corners(10)
+    ///     .iter()
+    ///     .map(|(x, y)| (x.round() as i32, y.round() as i32))
+    ///     .collect();
+    ///
+    /// assert_eq!(corners, vec![(0, -5), (-5, 0), (0, 5), (5, 0)]);
+    /// ```
+    pub fn get_corners(&self, size: u32) -> Vec<(f32, f32)> {
+        let (corners, radius, start) = match self {
+            NodeShape::Square => (4, SQRT_2 / 2.0, 3.0 * PI / 4.0),
+            NodeShape::Circle => (CIRCLE_CORNERS, 0.5, 0.0),
+            NodeShape::Octagon => (8, 0.5 / FRAC_PI_8.cos(), FRAC_PI_8),
+            NodeShape::Polygon(corners) => ((*corners).max(3), 0.5, FRAC_PI_2),
+        };
+        let radius = radius * size as f32;
+
+        (0..corners)
+            .map(|i| {
+                let angle = start + TAU * i as f32 / corners as f32;
+                (radius * angle.cos(), -radius * angle.sin())
+            })
+            .collect()
+    }
+
+    /// Returns the radius of the largest circle inside the shape for a size.
+    /// Walls stop at this distance from the node's center, so they never leave a gap to it.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::node::NodeShape;
+    /// assert_eq!(NodeShape::Square.get_half(9), 4);
+    /// assert_eq!(NodeShape::Octagon.get_half(10), 5);
+    /// assert_eq!(NodeShape::Polygon(3).get_half(10), 2);
+    /// ```
+    pub fn get_half(&self, size: u32) -> u32 {
+        match self {
+            NodeShape::Square | NodeShape::Circle | NodeShape::Octagon => size / 2,
+            NodeShape::Polygon(corners) => {
+                let corners = (*corners).max(3) as f32;
+                (size as f32 * 0.5 * (PI / corners).cos()) as u32
+            }
+        }
+    }
+}
 
 /// Defines how to render a node, where [`walls`](crate::tilemap::border::Border::Wall) intersect.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct NodeStyle {
     name: String,
     style: BoxStyle,
+    shape: NodeShape,
     size: u32,
     half: u32,
+    height: Option<u32>,
 }
 
 impl NodeStyle {
     pub fn new<S: Into<String>>(name: S, style: BoxStyle, size: u32) -> Self {
+        Self::with_shape(name, style, NodeShape::Square, size, None)
+    }
+
+    /// Creates a style with its own height instead of the one of its walls.
+    pub fn with_height<S: Into<String>>(name: S, style: BoxStyle, size: u32, height: u32) -> Self {
+        Self::with_shape(name, style, NodeShape::Square, size, Some(height))
+    }
+
+    /// Creates a style with a [`NodeShape`] other than a square, e.g. for round towers.
+    /// The 3d views render it as a prism.
+    pub fn with_shape<S: Into<String>>(
+        name: S,
+        style: BoxStyle,
+        shape: NodeShape,
+        size: u32,
+        height: Option<u32>,
+    ) -> Self {
         NodeStyle {
             name: name.into(),
             style,
+            shape,
             size,
-            half: size / 2,
+            half: shape.get_half(size),
+            height,
         }
     }
 
@@ -25,13 +115,23 @@ impl NodeStyle {
         &self.style
     }
 
+    pub fn get_shape(&self) -> NodeShape {
+        self.shape
+    }
+
     pub fn get_size(&self) -> u32 {
         self.size
     }
 
+    /// Returns the distance between the node's center & the ends of its walls.
     pub fn get_half(&self) -> u32 {
         self.half
     }
+
+    /// Returns the height in the 3d views, if it differs from the height of the walls.
+    pub fn get_height(&self) -> Option<u32> {
+        self.height
+    }
 }
 
 impl Default for NodeStyle {
@@ -0,0 +1,354 @@
+use crate::math::color::Color;
+use crate::math::point2d::Point2d;
+use crate::utils::hash::hash2d_unit;
+
+/// The width of joints & seams relative to the tile.
+const JOINT: f32 = 0.02;
+
+/// A point of a [`PatternShape`]. Both coordinates range from 0 to 1 across a tile.
+pub type PatternPoint = (f32, f32);
+
+/// A polygon rendered on top of the background of a [`Pattern`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternShape {
+    points: Vec<PatternPoint>,
+    color: Color,
+}
+
+impl PatternShape {
+    pub fn new(points: Vec<PatternPoint>, color: Color) -> Self {
+        PatternShape { points, color }
+    }
+
+    /// Returns an axis aligned rectangle.
+    fn rectangle(x0: f32, y0: f32, x1: f32, y1: f32, color: Color) -> Self {
+        Self::new(vec![(x0, y0), (x0, y1), (x1, y1), (x1, y0)], color)
+    }
+
+    pub fn get_points(&self) -> &[PatternPoint] {
+        &self.points
+    }
+
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    /// Is the point inside the polygon? Uses the even-odd rule.
+    fn contains(&self, u: f32, v: f32) -> bool {
+        let mut is_inside = false;
+        let mut previous = self.points[self.points.len() - 1];
+
+        for current in &self.points {
+            if (current.1 > v) != (previous.1 > v) {
+                let crossing = current.0
+                    + (previous.0 - current.0) * (v - current.1) / (previous.1 - current.1);
+
+                if u < crossing {
+                    is_inside = !is_inside;
+                }
+            }
+
+            previous = *current;
+        }
+
+        is_inside
+    }
+}
+
+/// A procedural pattern repeated on each [`floor tile`](crate::tilemap::tile::Tile::Floor).
+///
+/// The pattern is defined in the coordinates of a tile, which range from 0 to 1 along its x- & y-axis.
+/// The views map these coordinates to the projected tile, so the pattern follows the perspective.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Pattern {
+    /// n x n cells alternating between the background & another color.
+    Checkerboard { color: Color, cells: u32 },
+    /// Rows of planks with staggered seams of another color.
+    Planks { color: Color, rows: u32 },
+    /// Rows of irregular stones separated by joints of another color.
+    Flagstones { color: Color, rows: u32 },
+    /// Diagonal lines of another color.
+    Hatching { color: Color, lines: u32 },
+    /// n x n cells with a random brightness. The strength is the maximal darkening in percent.
+    /// The cells differ between tiles & continue across their edges,
+    /// so vector renderers need a pattern definition per tile.
+    Noise { cells: u32, strength: u32 },
+}
+
+impl Pattern {
+    /// Returns a copy with its color modulated by a light. The background is lit by the caller.
+    pub fn illuminate(&self, light: Color) -> Pattern {
+        match self.clone() {
+            Pattern::Checkerboard { color, cells } => Pattern::Checkerboard {
+                color: color * light,
+                cells,
+            },
+            Pattern::Planks { color, rows } => Pattern::Planks {
+                color: color * light,
+                rows,
+            },
+            Pattern::Flagstones { color, rows } => Pattern::Flagstones {
+                color: color * light,
+                rows,
+            },
+            Pattern::Hatching { color, lines } => Pattern::Hatching {
+                color: color * light,
+                lines,
+            },
+            noise => noise,
+        }
+    }
+
+    /// Does the pattern differ between tiles? Otherwise it is the same on every tile.
+    pub fn varies_per_tile(&self) -> bool {
+        matches!(self, Pattern::Noise { .. })
+    }
+
+    /// Returns the polygons rendered on top of the background color of the tile at a position of the tilemap.
+    pub fn get_shapes(&self, background: Color, tile: Point2d) -> Vec<PatternShape> {
+        match *self {
+            Pattern::Checkerboard { color, cells } => get_checkerboard(color, cells.max(1)),
+            Pattern::Planks { color, rows } => get_planks(color, rows.max(1)),
+            Pattern::Flagstones { color, rows } => get_flagstones(color, rows.max(1)),
+            Pattern::Hatching { color, lines } => get_hatching(color, lines.max(1)),
+            Pattern::Noise { cells, strength } => {
+                get_noise(background, cells.max(1), strength, tile)
+            }
+        }
+    }
+
+    /// Returns the color of the pattern at a point of the tilemap, e.g. for raster images.
+    /// The integer part of the coordinates selects the tile, so neighboring tiles continue the pattern.
+    ///
+    /// ```
+    ///# use tilemap::math::color::{BLACK, WHITE};
+    ///# use tilemap::renderer::style::pattern::Pattern;
+    /// let pattern = Pattern::Checkerboard { color: BLACK, cells: 2 };
+    ///
+    /// assert_eq!(pattern.get_color(WHITE, 0.25, 0.25), WHITE);
+    /// assert_eq!(pattern.get_color(WHITE, 0.75, 0.25), BLACK);
+    /// assert_eq!(pattern.get_color(WHITE, 1.75, 0.25), BLACK);
+    /// ```
+    pub fn get_color(&self, background: Color, u: f32, v: f32) -> Color {
+        let tile = Point2d::new(u.floor() as i32, v.floor() as i32);
+        let u = u.rem_euclid(1.0);
+        let v = v.rem_euclid(1.0);
+
+        self.get_shapes(background, tile)
+            .iter()
+            .rev()
+            .find(|shape| shape.contains(u, v))
+            .map(|shape| shape.color)
+            .unwrap_or(background)
+    }
+}
+
+fn get_checkerboard(color: Color, cells: u32) -> Vec<PatternShape> {
+    let size = 1.0 / cells as f32;
+    let mut shapes = Vec::new();
+
+    for y in 0..cells {
+        for x in 0..cells {
+            if (x + y) % 2 == 1 {
+                let x0 = x as f32 * size;
+                let y0 = y as f32 * size;
+                shapes.push(PatternShape::rectangle(x0, y0, x0 + size, y0 + size, color));
+            }
+        }
+    }
+
+    shapes
+}
+
+fn get_planks(color: Color, rows: u32) -> Vec<PatternShape> {
+    let height = 1.0 / rows as f32;
+    let mut shapes = Vec::new();
+
+    for row in 0..rows {
+        let y0 = row as f32 * height;
+        let seam = if row % 2 == 0 { 0.0 } else { 0.5 };
+
+        shapes.push(PatternShape::rectangle(0.0, y0, 1.0, y0 + JOINT, color));
+        shapes.push(PatternShape::rectangle(
+            seam,
+            y0,
+            seam + JOINT,
+            y0 + height,
+            color,
+        ));
+    }
+
+    shapes
+}
+
+fn get_flagstones(color: Color, rows: u32) -> Vec<PatternShape> {
+    let height = 1.0 / rows as f32;
+    let mut shapes = Vec::new();
+
+    for row in 0..rows {
+        let y0 = row as f32 * height;
+        let joint0 = (row as f32 * 0.35).fract();
+        let joint1 = (joint0 + 0.55).fract();
+
+        shapes.push(PatternShape::rectangle(0.0, y0, 1.0, y0 + JOINT, color));
+
+        for joint in [joint0, joint1] {
+            shapes.push(PatternShape::rectangle(
+                joint,
+                y0,
+                joint + JOINT,
+                y0 + height,
+                color,
+            ));
+        }
+    }
+
+    shapes
+}
+
+fn get_hatching(color: Color, lines: u32) -> Vec<PatternShape> {
+    let distance = 1.0 / lines as f32;
+    let width = distance * 0.3;
+
+    // The lines fulfill u + v = t, which ranges from 0 to 2 inside a tile.
+    (0..(2 * lines))
+        .map(|i| {
+            let t = i as f32 * distance;
+            PatternShape::new(
+                vec![
+                    (t, 0.0),
+                    (t - 1.0, 1.0),
+                    (t + width - 1.0, 1.0),
+                    (t + width, 0.0),
+                ],
+                color,
+            )
+        })
+        .collect()
+}
+
+/// The cells are hashed by their position in the tilemap, so the noise continues across tiles.
+fn get_noise(background: Color, cells: u32, strength: u32, tile: Point2d) -> Vec<PatternShape> {
+    let size = 1.0 / cells as f32;
+    let strength = strength.min(100) as f32 / 100.0;
+    let start_x = (tile.x as u32).wrapping_mul(cells);
+    let start_y = (tile.y as u32).wrapping_mul(cells);
+    let mut shapes = Vec::new();
+
+    for y in 0..cells {
+        for x in 0..cells {
+            let hash = hash2d_unit(start_x.wrapping_add(x), start_y.wrapping_add(y), cells);
+            let factor = 1.0 - strength * hash;
+            let x0 = x as f32 * size;
+            let y0 = y as f32 * size;
+            shapes.push(PatternShape::rectangle(
+                x0,
+                y0,
+                x0 + size,
+                y0 + size,
+                background * factor,
+            ));
+        }
+    }
+
+    shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::{BLACK, GREEN, WHITE};
+
+    #[test]
+    fn test_checkerboard() {
+        let pattern = Pattern::Checkerboard {
+            color: BLACK,
+            cells: 4,
+        };
+
+        assert_eq!(pattern.get_shapes(WHITE, Point2d::default()).len(), 8);
+        assert_eq!(pattern.get_color(WHITE, 0.1, 0.1), WHITE);
+        assert_eq!(pattern.get_color(WHITE, 0.3, 0.1), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.3, 0.3), WHITE);
+        assert_eq!(pattern.get_color(WHITE, 0.1, 0.3), BLACK);
+    }
+
+    #[test]
+    fn test_planks_have_staggered_seams() {
+        let pattern = Pattern::Planks {
+            color: BLACK,
+            rows: 2,
+        };
+
+        assert_eq!(pattern.get_color(WHITE, 0.25, 0.25), WHITE);
+        assert_eq!(pattern.get_color(WHITE, 0.01, 0.25), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.01, 0.75), WHITE);
+        assert_eq!(pattern.get_color(WHITE, 0.51, 0.75), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.25, 0.51), BLACK);
+    }
+
+    #[test]
+    fn test_flagstones() {
+        let pattern = Pattern::Flagstones {
+            color: BLACK,
+            rows: 2,
+        };
+
+        assert_eq!(pattern.get_shapes(WHITE, Point2d::default()).len(), 6);
+        assert_eq!(pattern.get_color(WHITE, 0.3, 0.25), WHITE);
+        assert_eq!(pattern.get_color(WHITE, 0.56, 0.25), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.36, 0.75), BLACK);
+    }
+
+    #[test]
+    fn test_hatching_wraps_around() {
+        let pattern = Pattern::Hatching {
+            color: BLACK,
+            lines: 4,
+        };
+
+        assert_eq!(pattern.get_color(WHITE, 0.5, 0.01), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.01, 0.5), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.9, 0.11), BLACK);
+        assert_eq!(pattern.get_color(WHITE, 0.6, 0.01), WHITE);
+    }
+
+    #[test]
+    fn test_noise_is_deterministic() {
+        let pattern = Pattern::Noise {
+            cells: 8,
+            strength: 20,
+        };
+
+        let tile = Point2d::new(3, 4);
+
+        assert_eq!(
+            pattern.get_shapes(GREEN, tile),
+            pattern.get_shapes(GREEN, tile)
+        );
+        assert_eq!(pattern.get_shapes(GREEN, tile).len(), 64);
+
+        for shape in pattern.get_shapes(GREEN, tile) {
+            assert!(shape.get_color().g() >= 204);
+        }
+    }
+
+    #[test]
+    fn test_noise_differs_between_tiles() {
+        let pattern = Pattern::Noise {
+            cells: 4,
+            strength: 50,
+        };
+
+        assert!(pattern.varies_per_tile());
+        assert_ne!(
+            pattern.get_shapes(GREEN, Point2d::new(0, 0)),
+            pattern.get_shapes(GREEN, Point2d::new(1, 0))
+        );
+        // the cells continue across the edge of the tiles
+        assert_eq!(
+            pattern.get_color(GREEN, 1.1, 0.1),
+            pattern.get_shapes(GREEN, Point2d::new(1, 0))[0].get_color()
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::math::color::Color;
+use crate::renderer::shadow::Sun;
+use crate::renderer::style::aab::BoxStyle;
+
+/// A 3d vector in the coordinates of the tilemap, where the z-axis points up.
+pub type Vector3d = (f32, f32, f32);
+
+const TOP: Vector3d = (0.0, 0.0, 1.0);
+const FRONT: Vector3d = (0.0, 1.0, 0.0);
+const BACK: Vector3d = (0.0, -1.0, 0.0);
+const RIGHT: Vector3d = (1.0, 0.0, 0.0);
+const LEFT: Vector3d = (-1.0, 0.0, 0.0);
+
+/// Derives the colors of a [`BoxStyle`] from a base color with
+/// [Lambertian reflectance](https://en.wikipedia.org/wiki/Lambertian_reflectance) of a directional light.
+///
+/// The brightness of a face is the ambient term plus the rest scaled by the cosine between the face & the light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadingModel {
+    direction: Vector3d,
+    ambient: f32,
+}
+
+impl ShadingModel {
+    /// The direction points towards the light & gets normalized. The ambient term is clamped to [0, 1].
+    pub fn new(direction: Vector3d, ambient: f32) -> Self {
+        let (x, y, z) = direction;
+        let length = (x * x + y * y + z * z).sqrt();
+        let direction = if length > 0.0 {
+            (x / length, y / length, z / length)
+        } else {
+            TOP
+        };
+
+        ShadingModel {
+            direction,
+            ambient: ambient.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Creates a model, where the light comes from the same direction as the [`Sun`] casting the shadows.
+    pub fn from_sun(sun: &Sun, ambient: f32) -> Self {
+        let (x, y) = sun.get_direction();
+        Self::new((-x, -y, 1.0), ambient)
+    }
+
+    pub fn get_direction(&self) -> Vector3d {
+        self.direction
+    }
+
+    pub fn get_ambient(&self) -> f32 {
+        self.ambient
+    }
+
+    /// Returns the brightness between the ambient term & 1 of a face with a specific normal.
+    pub fn get_brightness(&self, normal: Vector3d) -> f32 {
+        let (x, y, z) = self.direction;
+        let cosine = normal.0 * x + normal.1 * y + normal.2 * z;
+
+        self.ambient + (1.0 - self.ambient) * cosine.max(0.0)
+    }
+
+    /// Returns the style of a box with a specific color, that has colors for all faces.
+    ///
+    /// ```
+    ///# use tilemap::math::color::{Color, WHITE};
+    ///# use tilemap::renderer::style::aab::BoxStyle;
+    ///# use tilemap::renderer::style::shading::ShadingModel;
+    /// let model = ShadingModel::new((0.0, 0.0, 2.0), 0.5);
+    /// let gray = Color::gray(127);
+    ///
+    /// assert_eq!(model.shade(WHITE), BoxStyle::with_hidden_faces(gray, gray, WHITE, gray, gray));
+    /// ```
+    pub fn shade(&self, color: Color) -> BoxStyle {
+        let shade = |normal| color * self.get_brightness(normal);
+
+        BoxStyle::with_hidden_faces(
+            shade(FRONT),
+            shade(RIGHT),
+            shade(TOP),
+            shade(BACK),
+            shade(LEFT),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::{BLACK, WHITE};
+
+    #[test]
+    fn test_light_from_front_right() {
+        let model = ShadingModel::new((1.0, 1.0, 0.0), 0.2);
+        let style = model.shade(WHITE);
+
+        assert_eq!(*style.get_front_color(), *style.get_side_color());
+        assert_eq!(*style.get_back_color(), Color::gray(51));
+        assert_eq!(*style.get_left_color(), Color::gray(51));
+        assert_eq!(*style.get_top_color(), Color::gray(51));
+        assert!(style.get_front_color().r() > 51);
+    }
+
+    #[test]
+    fn test_from_sun() {
+        let sun = Sun::new(0.0, -1.0, BLACK);
+        let model = ShadingModel::from_sun(&sun, 0.0);
+        let style = model.shade(WHITE);
+
+        assert_eq!(*style.get_front_color(), Color::gray(180));
+        assert_eq!(*style.get_top_color(), Color::gray(180));
+        assert_eq!(*style.get_back_color(), BLACK);
+        assert_eq!(*style.get_side_color(), BLACK);
+    }
+
+    #[test]
+    fn test_invalid_direction() {
+        let model = ShadingModel::new((0.0, 0.0, 0.0), 2.0);
+
+        assert_eq!(model.get_direction(), TOP);
+        assert_eq!(model.get_ambient(), 1.0);
+    }
+}
@@ -0,0 +1,974 @@
+use crate::math::color::Color;
+use crate::math::size2d::Size2d;
+use crate::renderer::style::aab::BoxStyle;
+use crate::renderer::style::floor::FloorStyle;
+use crate::renderer::style::grid::{GridPosition, GridStyle, LineStyle};
+use crate::renderer::style::image::{Image, ImageRegion};
+use crate::renderer::style::jitter::ColorJitter;
+use crate::renderer::style::node::{NodeShape, NodeStyle};
+use crate::renderer::style::solid::SolidStyle;
+use crate::renderer::style::wall::{JunctionNodes, WallAlignment, WallJoin, WallStyle};
+use crate::renderer::style::StyleMgr;
+use crate::tilemap::NodeId;
+use crate::utils::resource::ResourceManager;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// The definition of an [`Image`] in a style sheet.
+///
+/// Either the path of a file, e.g. `"grass.png"`,
+/// or a sprite of a sprite sheet with its size,
+/// e.g. `{ path = "sheet.png", sheet_width = 64, sheet_height = 32, x = 0, y = 16, width = 16, height = 16 }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ImageDefinition {
+    File(String),
+    Sprite {
+        path: String,
+        sheet_width: u32,
+        sheet_height: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// The definition of a [`BoxStyle`] borrowed from the keys of a node, solid or wall style.
+///
+/// Either a single color, that is [`shaded`](BoxStyle::shaded), or a color per face.
+/// Optionally with an image for the top face & one for the vertical faces.
+#[derive(Debug)]
+struct BoxDefinition<'a> {
+    color: Option<&'a str>,
+    front: Option<&'a str>,
+    side: Option<&'a str>,
+    top: Option<&'a str>,
+    top_image: Option<&'a ImageDefinition>,
+    side_image: Option<&'a ImageDefinition>,
+}
+
+/// The definition of a [`ColorJitter`] in a style sheet, e.g. `{ seed = 3, strength = 10 }`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JitterDefinition {
+    #[serde(default)]
+    seed: u32,
+    strength: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FloorDefinition {
+    name: String,
+    color: String,
+    image: Option<ImageDefinition>,
+    jitter: Option<JitterDefinition>,
+}
+
+/// The definition of a [`NodeShape`] in a style sheet, e.g. `"circle"` or `{ polygon = 6 }`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ShapeDefinition {
+    #[default]
+    Square,
+    Circle,
+    Octagon,
+    Polygon(u32),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeDefinition {
+    name: String,
+    color: Option<String>,
+    front: Option<String>,
+    side: Option<String>,
+    top: Option<String>,
+    top_image: Option<ImageDefinition>,
+    side_image: Option<ImageDefinition>,
+    #[serde(default)]
+    shape: ShapeDefinition,
+    size: u32,
+    height: Option<u32>,
+}
+
+impl NodeDefinition {
+    fn get_box(&self) -> BoxDefinition<'_> {
+        BoxDefinition {
+            color: self.color.as_deref(),
+            front: self.front.as_deref(),
+            side: self.side.as_deref(),
+            top: self.top.as_deref(),
+            top_image: self.top_image.as_ref(),
+            side_image: self.side_image.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SolidDefinition {
+    name: String,
+    color: Option<String>,
+    front: Option<String>,
+    side: Option<String>,
+    top: Option<String>,
+    top_image: Option<ImageDefinition>,
+    side_image: Option<ImageDefinition>,
+    height: Option<u32>,
+    jitter: Option<JitterDefinition>,
+}
+
+impl SolidDefinition {
+    fn get_box(&self) -> BoxDefinition<'_> {
+        BoxDefinition {
+            color: self.color.as_deref(),
+            front: self.front.as_deref(),
+            side: self.side.as_deref(),
+            top: self.top.as_deref(),
+            top_image: self.top_image.as_ref(),
+            side_image: self.side_image.as_ref(),
+        }
+    }
+}
+
+/// The definition of a [`WallJoin`] in a style sheet, e.g. `"mitered"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JoinDefinition {
+    #[default]
+    Node,
+    Filled,
+    Mitered,
+}
+
+/// The definition of a [`WallAlignment`] in a style sheet, e.g. `"back-left"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum AlignmentDefinition {
+    #[default]
+    Centered,
+    BackLeft,
+    FrontRight,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WallDefinition {
+    name: String,
+    color: Option<String>,
+    front: Option<String>,
+    side: Option<String>,
+    top: Option<String>,
+    top_image: Option<ImageDefinition>,
+    side_image: Option<ImageDefinition>,
+    /// The name of the node style, which is only optional for joined walls.
+    node: Option<String>,
+    /// The names of the node styles of specific junctions instead of the node style.
+    #[serde(default)]
+    junctions: JunctionsDefinition,
+    #[serde(default)]
+    join: JoinDefinition,
+    #[serde(default)]
+    alignment: AlignmentDefinition,
+    thickness: u32,
+    height: Option<u32>,
+}
+
+impl WallDefinition {
+    fn get_box(&self) -> BoxDefinition<'_> {
+        BoxDefinition {
+            color: self.color.as_deref(),
+            front: self.front.as_deref(),
+            side: self.side.as_deref(),
+            top: self.top.as_deref(),
+            top_image: self.top_image.as_ref(),
+            side_image: self.side_image.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JunctionsDefinition {
+    end: Option<String>,
+    corner: Option<String>,
+    t_junction: Option<String>,
+    cross: Option<String>,
+}
+
+/// The definition of the lines of a [`GridStyle`] in a style sheet, e.g. `"solid"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum GridLineDefinition {
+    Solid,
+    #[default]
+    Dotted,
+}
+
+/// The definition of a [`GridPosition`] in a style sheet, e.g. `"below-walls"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum GridPositionDefinition {
+    Hidden,
+    BelowWalls,
+    #[default]
+    AboveWalls,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GridDefinition {
+    color: String,
+    #[serde(default)]
+    style: GridLineDefinition,
+    #[serde(default)]
+    position: GridPositionDefinition,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StyleSheet {
+    floors: Vec<FloorDefinition>,
+    nodes: Vec<NodeDefinition>,
+    solids: Vec<SolidDefinition>,
+    walls: Vec<WallDefinition>,
+    grid: Option<GridDefinition>,
+}
+
+/// Loads a [`StyleMgr`] from a style sheet in the [TOML](https://toml.io) format.
+pub fn load_style_sheet<P: AsRef<Path>>(path: P) -> Result<StyleMgr> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).context(format!(
+        "Failed to read the style sheet '{}'",
+        path.display()
+    ))?;
+
+    parse_style_sheet(&text).context(format!(
+        "Failed to load the style sheet '{}'",
+        path.display()
+    ))
+}
+
+/// Parses a [`StyleMgr`] from a style sheet in the [TOML](https://toml.io) format.
+///
+/// All styles are defined by unique names & colors are hex triplets.
+/// Walls refer to their node style by name & can override it for specific junctions.
+/// Joined walls don't need a node style.
+/// Solids, nodes & walls have an optional height & nodes an optional shape.
+/// Floors & the faces of solids, nodes & walls can use images or sprites of a sprite sheet.
+/// The colors of floors & solids can vary per tile with a seeded jitter.
+/// The grid has a color, an optional line style & an optional position.
+///
+/// ```
+///# use tilemap::renderer::style::sheet::parse_style_sheet;
+///# use tilemap::math::color::Color;
+///# use tilemap::renderer::node::Junction;
+///# use tilemap::tilemap::border::WallId;
+///# use tilemap::tilemap::tile::FloorId;
+///# use tilemap::tilemap::NodeId;
+/// let styles = parse_style_sheet(r##"
+/// [[floors]]
+/// name = "stone"
+/// color = "#808080"
+///
+/// [[nodes]]
+/// name = "pillar"
+/// color = "#ff0000"
+/// size = 20
+///
+/// [[nodes]]
+/// name = "cap"
+/// color = "#ff0000"
+/// shape = "circle"
+/// size = 12
+///
+/// [[walls]]
+/// name = "brick"
+/// front = "#800000"
+/// side = "#600000"
+/// top = "#a00000"
+/// node = "pillar"
+/// junctions = { end = "cap" }
+/// thickness = 10
+///
+/// [grid]
+/// color = "#000000"
+/// style = "solid"
+/// position = "below-walls"
+/// "##).unwrap();
+///
+/// assert_eq!(styles.get_floor_style(FloorId(0)).get_floor_color(), &Color::gray(128));
+/// let wall = styles.get_wall_style(WallId(0));
+///
+/// assert_eq!(wall.get_node_style(Junction::Corner), NodeId(0));
+/// assert_eq!(wall.get_node_style(Junction::End), NodeId(1));
+/// assert_eq!(styles.get_node_style(NodeId(0)).get_size(), 20);
+/// ```
+pub fn parse_style_sheet(text: &str) -> Result<StyleMgr> {
+    let sheet: StyleSheet = toml::from_str(text).context("Failed to parse the style sheet")?;
+
+    let mut floors = ResourceManager::empty(Default::default());
+
+    for floor in &sheet.floors {
+        parse_floor(floor)
+            .and_then(|style| floors.add(style))
+            .context(format!("Invalid floor style '{}'", floor.name))?;
+    }
+
+    let mut nodes = ResourceManager::empty(Default::default());
+
+    for node in &sheet.nodes {
+        parse_box(&node.get_box())
+            .and_then(|aab| parse_node(node, aab))
+            .and_then(|style| nodes.add(style))
+            .context(format!("Invalid node style '{}'", node.name))?;
+    }
+
+    let mut solids = ResourceManager::empty(Default::default());
+
+    for solid in &sheet.solids {
+        parse_box(&solid.get_box())
+            .and_then(|aab| parse_solid(solid, aab))
+            .and_then(|style| solids.add(style))
+            .context(format!("Invalid solid style '{}'", solid.name))?;
+    }
+
+    let mut walls = ResourceManager::empty(Default::default());
+
+    for wall in &sheet.walls {
+        parse_wall(wall, &nodes)
+            .and_then(|style| walls.add(style))
+            .context(format!("Invalid wall style '{}'", wall.name))?;
+    }
+
+    let grid = match &sheet.grid {
+        Some(grid) => parse_grid(grid).context("Invalid grid style")?,
+        None => GridStyle::default(),
+    };
+
+    Ok(StyleMgr::new(floors, nodes, solids, walls, grid))
+}
+
+fn parse_grid(grid: &GridDefinition) -> Result<GridStyle> {
+    let color = Color::from_hex(&grid.color)?;
+    let line = match grid.style {
+        GridLineDefinition::Solid => LineStyle::solid(color, 1),
+        GridLineDefinition::Dotted => GridStyle::dotted("grid", color).get_line_style().clone(),
+    };
+    let position = match grid.position {
+        GridPositionDefinition::Hidden => GridPosition::Hidden,
+        GridPositionDefinition::BelowWalls => GridPosition::BelowWalls,
+        GridPositionDefinition::AboveWalls => GridPosition::AboveWalls,
+    };
+
+    Ok(GridStyle::new("grid", line, None, None, position))
+}
+
+fn parse_wall(
+    wall: &WallDefinition,
+    nodes: &ResourceManager<NodeId, NodeStyle>,
+) -> Result<WallStyle> {
+    let aab = parse_box(&wall.get_box())?;
+    let join = match wall.join {
+        JoinDefinition::Node => WallJoin::Node,
+        JoinDefinition::Filled => WallJoin::Filled,
+        JoinDefinition::Mitered => WallJoin::Mitered,
+    };
+    let alignment = match wall.alignment {
+        AlignmentDefinition::Centered => WallAlignment::Centered,
+        AlignmentDefinition::BackLeft => WallAlignment::BackLeft,
+        AlignmentDefinition::FrontRight => WallAlignment::FrontRight,
+    };
+    let get_node = |name: &Option<String>| match name.as_ref().or(wall.node.as_ref()) {
+        Some(name) => match nodes.get_id(name) {
+            Some(id) => Ok(id),
+            None => bail!("Unknown node style '{}'", name),
+        },
+        // the node style of joined walls is never rendered
+        None if join != WallJoin::Node => Ok(NodeId::default()),
+        None => bail!("Missing node style"),
+    };
+    let junctions = &wall.junctions;
+    let nodes = JunctionNodes::new(
+        get_node(&junctions.end)?,
+        get_node(&junctions.corner)?,
+        get_node(&junctions.t_junction)?,
+        get_node(&junctions.cross)?,
+    );
+
+    Ok(
+        WallStyle::with_junctions(&wall.name, aab, nodes, wall.thickness, wall.height)
+            .joined(join)
+            .aligned(alignment),
+    )
+}
+
+fn parse_node(node: &NodeDefinition, aab: BoxStyle) -> Result<NodeStyle> {
+    let shape = match node.shape {
+        ShapeDefinition::Square => NodeShape::Square,
+        ShapeDefinition::Circle => NodeShape::Circle,
+        ShapeDefinition::Octagon => NodeShape::Octagon,
+        ShapeDefinition::Polygon(corners) if corners < 3 => {
+            bail!("A polygon needs at least 3 corners, but has {}", corners)
+        }
+        ShapeDefinition::Polygon(corners) => NodeShape::Polygon(corners),
+    };
+
+    Ok(NodeStyle::with_shape(
+        &node.name,
+        aab,
+        shape,
+        node.size,
+        node.height,
+    ))
+}
+
+fn parse_solid(solid: &SolidDefinition, aab: BoxStyle) -> Result<SolidStyle> {
+    let style = match solid.height {
+        Some(height) => SolidStyle::with_height(&solid.name, aab, height),
+        None => SolidStyle::new(&solid.name, aab),
+    };
+
+    Ok(match &solid.jitter {
+        Some(jitter) => style.jittered(parse_jitter(jitter)?),
+        None => style,
+    })
+}
+
+fn parse_jitter(definition: &JitterDefinition) -> Result<ColorJitter> {
+    if definition.strength > 100 {
+        bail!(
+            "The strength of the jitter is {}%, but at most 100%",
+            definition.strength
+        );
+    }
+
+    Ok(ColorJitter::new(definition.seed, definition.strength))
+}
+
+fn parse_floor(floor: &FloorDefinition) -> Result<FloorStyle> {
+    let color = Color::from_hex(&floor.color)?;
+    let style = match &floor.image {
+        Some(image) => FloorStyle::with_image(&floor.name, color, parse_image(image)?),
+        None => FloorStyle::new(&floor.name, color),
+    };
+
+    Ok(match &floor.jitter {
+        Some(jitter) => style.jittered(parse_jitter(jitter)?),
+        None => style,
+    })
+}
+
+fn parse_box(definition: &BoxDefinition) -> Result<BoxStyle> {
+    let style = match definition {
+        BoxDefinition {
+            color: Some(color),
+            front: None,
+            side: None,
+            top: None,
+            ..
+        } => BoxStyle::shaded(Color::from_hex(color)?),
+        BoxDefinition {
+            color: None,
+            front: Some(front),
+            side: Some(side),
+            top: Some(top),
+            ..
+        } => BoxStyle::new(
+            Color::from_hex(front)?,
+            Color::from_hex(side)?,
+            Color::from_hex(top)?,
+        ),
+        _ => bail!("Requires either a color or the colors of the front, side & top faces"),
+    };
+
+    let top = definition.top_image.map(parse_image).transpose()?;
+    let sides = definition.side_image.map(parse_image).transpose()?;
+
+    Ok(style.textured(top, sides))
+}
+
+fn parse_image(definition: &ImageDefinition) -> Result<Image> {
+    match definition {
+        ImageDefinition::File(path) => Ok(Image::new(path)),
+        ImageDefinition::Sprite { width, height, .. } if *width == 0 || *height == 0 => {
+            bail!("The sprite has no area")
+        }
+        ImageDefinition::Sprite {
+            sheet_width,
+            sheet_height,
+            x,
+            y,
+            width,
+            height,
+            ..
+        } if x.saturating_add(*width) > *sheet_width
+            || y.saturating_add(*height) > *sheet_height =>
+        {
+            bail!("The sprite is outside of the sprite sheet")
+        }
+        ImageDefinition::Sprite {
+            path,
+            sheet_width,
+            sheet_height,
+            x,
+            y,
+            width,
+            height,
+        } => Ok(Image::sprite(
+            path,
+            Size2d::new(*sheet_width, *sheet_height),
+            ImageRegion::new(*x, *y, Size2d::new(*width, *height)),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::{BLUE, RED};
+    use crate::renderer::node::Junction;
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::tile::{FloorId, SolidId};
+
+    #[test]
+    fn test_empty_style_sheet() {
+        let styles = parse_style_sheet("").unwrap();
+
+        assert!(styles.get_node_styles().is_empty());
+        assert!(styles.get_wall_styles().is_empty());
+        assert_eq!(styles.get_grid_style(), &GridStyle::default());
+    }
+
+    #[test]
+    fn test_resolve_node_by_name() {
+        let styles = parse_style_sheet(
+            r##"
+            [[nodes]]
+            name = "small"
+            color = "#ff0000"
+            size = 10
+
+            [[nodes]]
+            name = "large"
+            color = "#0000ff"
+            size = 30
+
+            [[walls]]
+            name = "wall"
+            color = "#ff0000"
+            node = "large"
+            thickness = 20
+            "##,
+        )
+        .unwrap();
+
+        let wall = styles.get_wall_style(WallId(0));
+
+        assert_eq!(wall.get_aab_style(), &BoxStyle::shaded(RED));
+        assert_eq!(wall.get_node_style(Junction::Corner), NodeId(1));
+        assert_eq!(
+            styles
+                .get_node_style(wall.get_node_style(Junction::Cross))
+                .get_style(),
+            &BoxStyle::shaded(BLUE)
+        );
+    }
+
+    #[test]
+    fn test_optional_heights() {
+        let styles = parse_style_sheet(
+            r##"
+            [[nodes]]
+            name = "post"
+            color = "#ff0000"
+            size = 10
+            height = 30
+
+            [[solids]]
+            name = "rock"
+            color = "#ff0000"
+
+            [[walls]]
+            name = "fence"
+            color = "#ff0000"
+            node = "post"
+            thickness = 5
+            height = 20
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(styles.get_node_style(NodeId(0)).get_height(), Some(30));
+        assert_eq!(styles.get_solid_style(SolidId(0)).get_height(), None);
+        assert_eq!(styles.get_wall_style(WallId(0)).get_height(), Some(20));
+        assert_eq!(styles.get_max_height(), Some(30));
+    }
+
+    #[test]
+    fn test_node_shapes() {
+        let styles = parse_style_sheet(
+            r##"
+            [[nodes]]
+            name = "tower"
+            color = "#ff0000"
+            shape = "circle"
+            size = 20
+
+            [[nodes]]
+            name = "pillar"
+            color = "#ff0000"
+            shape = { polygon = 6 }
+            size = 10
+
+            [[nodes]]
+            name = "post"
+            color = "#ff0000"
+            size = 10
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            styles.get_node_style(NodeId(0)).get_shape(),
+            NodeShape::Circle
+        );
+        assert_eq!(
+            styles.get_node_style(NodeId(1)).get_shape(),
+            NodeShape::Polygon(6)
+        );
+        assert_eq!(
+            styles.get_node_style(NodeId(2)).get_shape(),
+            NodeShape::Square
+        );
+    }
+
+    #[test]
+    fn test_invalid_polygon() {
+        let error = parse_style_sheet(
+            r##"
+            [[nodes]]
+            name = "pillar"
+            color = "#ff0000"
+            shape = { polygon = 2 }
+            size = 10
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid node style 'pillar': A polygon needs at least 3 corners, but has 2"
+        );
+    }
+
+    #[test]
+    fn test_images() {
+        let styles = parse_style_sheet(
+            r##"
+            [[floors]]
+            name = "grass"
+            color = "#00ff00"
+            image = "grass.png"
+
+            [[solids]]
+            name = "crate"
+            color = "#ff0000"
+            top_image = { path = "sheet.png", sheet_width = 64, sheet_height = 32, x = 16, y = 0, width = 16, height = 8 }
+            side_image = "planks.png"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            styles.get_floor_style(FloorId(0)).get_image(),
+            Some(&Image::new("grass.png"))
+        );
+        let solid = styles.get_solid_style(SolidId(0)).get_aab_style();
+        assert_eq!(
+            solid.get_top_image(),
+            Some(&Image::sprite(
+                "sheet.png",
+                Size2d::new(64, 32),
+                ImageRegion::new(16, 0, Size2d::new(16, 8))
+            ))
+        );
+        assert_eq!(solid.get_side_image(), Some(&Image::new("planks.png")));
+    }
+
+    #[test]
+    fn test_empty_sprite() {
+        let error = parse_style_sheet(
+            r##"
+            [[solids]]
+            name = "crate"
+            color = "#ff0000"
+            top_image = { path = "sheet.png", sheet_width = 64, sheet_height = 32, x = 16, y = 0, width = 0, height = 8 }
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid solid style 'crate': The sprite has no area"
+        );
+    }
+
+    #[test]
+    fn test_sprite_outside_of_sheet() {
+        let error = parse_style_sheet(
+            r##"
+            [[solids]]
+            name = "crate"
+            color = "#ff0000"
+            top_image = { path = "sheet.png", sheet_width = 64, sheet_height = 32, x = 16, y = 24, width = 16, height = 16 }
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid solid style 'crate': The sprite is outside of the sprite sheet"
+        );
+    }
+
+    #[test]
+    fn test_jitter() {
+        let styles = parse_style_sheet(
+            r##"
+            [[floors]]
+            name = "grass"
+            color = "#00ff00"
+            jitter = { seed = 3, strength = 10 }
+
+            [[solids]]
+            name = "rock"
+            color = "#ff0000"
+            jitter = { strength = 5 }
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            styles.get_floor_style(FloorId(0)).get_jitter(),
+            Some(&ColorJitter::new(3, 10))
+        );
+        assert_eq!(
+            styles.get_solid_style(SolidId(0)).get_jitter(),
+            Some(&ColorJitter::new(0, 5))
+        );
+    }
+
+    #[test]
+    fn test_too_strong_jitter() {
+        let error = parse_style_sheet(
+            r##"
+            [[floors]]
+            name = "grass"
+            color = "#00ff00"
+            jitter = { seed = 3, strength = 150 }
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid floor style 'grass': The strength of the jitter is 150%, but at most 100%"
+        );
+    }
+
+    #[test]
+    fn test_joined_wall_without_node() {
+        let styles = parse_style_sheet(
+            r##"
+            [[walls]]
+            name = "wall"
+            color = "#ff0000"
+            join = "mitered"
+            alignment = "front-right"
+            thickness = 20
+            "##,
+        )
+        .unwrap();
+        let wall = styles.get_wall_style(WallId(0));
+
+        assert_eq!(wall.get_join(), WallJoin::Mitered);
+        assert_eq!(wall.get_alignment(), WallAlignment::FrontRight);
+    }
+
+    #[test]
+    fn test_missing_node() {
+        let error = parse_style_sheet(
+            r##"
+            [[walls]]
+            name = "wall"
+            color = "#ff0000"
+            thickness = 20
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid wall style 'wall': Missing node style"
+        );
+    }
+
+    #[test]
+    fn test_unknown_node() {
+        let error = parse_style_sheet(
+            r##"
+            [[walls]]
+            name = "wall"
+            color = "#ff0000"
+            node = "pillar"
+            thickness = 20
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid wall style 'wall': Unknown node style 'pillar'"
+        );
+    }
+
+    #[test]
+    fn test_unknown_junction_node() {
+        let error = parse_style_sheet(
+            r##"
+            [[nodes]]
+            name = "pillar"
+            color = "#ff0000"
+            size = 10
+
+            [[walls]]
+            name = "wall"
+            color = "#ff0000"
+            node = "pillar"
+            junctions = { cross = "tower" }
+            thickness = 20
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid wall style 'wall': Unknown node style 'tower'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_color() {
+        let error = parse_style_sheet(
+            r##"
+            [[floors]]
+            name = "stone"
+            color = "808080"
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid floor style 'stone': '808080' needs to start with # to be a color"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_name() {
+        let error = parse_style_sheet(
+            r##"
+            [[floors]]
+            name = "stone"
+            color = "#808080"
+
+            [[floors]]
+            name = "stone"
+            color = "#606060"
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid floor style 'stone': A resource with the name 'stone' already exists"
+        );
+    }
+
+    #[test]
+    fn test_incomplete_box() {
+        let error = parse_style_sheet(
+            r##"
+            [[solids]]
+            name = "rock"
+            front = "#808080"
+            "##,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", error),
+            "Invalid solid style 'rock': Requires either a color or the colors of the front, side & top faces"
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let styles = parse_style_sheet(
+            r##"
+            [grid]
+            color = "#ff0000"
+            style = "solid"
+            position = "hidden"
+            "##,
+        )
+        .unwrap();
+        let grid = styles.get_grid_style();
+
+        assert_eq!(grid.get_line_style(), &LineStyle::solid(RED, 1));
+        assert_eq!(grid.get_position(), GridPosition::Hidden);
+    }
+
+    #[test]
+    fn test_grid_defaults_to_dotted_above_walls() {
+        let styles = parse_style_sheet(
+            "[grid]
+color = \"#ff0000\"",
+        )
+        .unwrap();
+
+        assert_eq!(styles.get_grid_style(), &GridStyle::dotted("grid", RED));
+    }
+
+    #[test]
+    fn test_unknown_grid_position() {
+        assert!(parse_style_sheet("[grid]\ncolor = \"#ff0000\"\nposition = \"top\"").is_err());
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        assert!(parse_style_sheet("[grid]\ncolour = \"#000000\"").is_err());
+    }
+
+    #[test]
+    fn test_unknown_wall_field() {
+        let error = parse_style_sheet(
+            r##"
+            [[walls]]
+            name = "brick"
+            color = "#ff0000"
+            join = "mitered"
+            thickness = 10
+            heigth = 30
+            "##,
+        )
+        .unwrap_err();
+
+        assert!(format!("{:#}", error).contains("unknown field `heigth`"));
+    }
+}
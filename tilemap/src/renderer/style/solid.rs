@@ -1,5 +1,6 @@
 use crate::math::color::PINK;
 use crate::renderer::style::aab::BoxStyle;
+use crate::renderer::style::jitter::ColorJitter;
 use crate::utils::resource::Resource;
 
 /// Defines how to render a [`solid tile`](crate::tilemap::tile::Tile::Solid).
@@ -7,6 +8,8 @@ use crate::utils::resource::Resource;
 pub struct SolidStyle {
     name: String,
     aab: BoxStyle,
+    height: Option<u32>,
+    jitter: Option<ColorJitter>,
 }
 
 impl SolidStyle {
@@ -14,12 +17,47 @@ impl SolidStyle {
         SolidStyle {
             name: name.into(),
             aab,
+            height: None,
+            jitter: None,
+        }
+    }
+
+    /// Creates a style with its own height, e.g. for a low rock or a tall tower.
+    pub fn with_height<S: Into<String>>(name: S, aab: BoxStyle, height: u32) -> Self {
+        SolidStyle {
+            height: Some(height),
+            ..Self::new(name, aab)
+        }
+    }
+
+    /// Returns a copy, whose colors vary per tile.
+    pub fn jittered(&self, jitter: ColorJitter) -> Self {
+        SolidStyle {
+            jitter: Some(jitter),
+            ..self.clone()
         }
     }
 
     pub fn get_aab_style(&self) -> &BoxStyle {
         &self.aab
     }
+
+    /// Returns the [`BoxStyle`] of a specific tile, which differs from the box style with a jitter.
+    pub fn get_tile_style(&self, x: u32, y: u32) -> BoxStyle {
+        match &self.jitter {
+            None => self.aab.clone(),
+            Some(jitter) => self.aab.scaled(jitter.get_factor(x, y)),
+        }
+    }
+
+    pub fn get_jitter(&self) -> Option<&ColorJitter> {
+        self.jitter.as_ref()
+    }
+
+    /// Returns the height in the 3d views, if it differs from their tile height.
+    pub fn get_height(&self) -> Option<u32> {
+        self.height
+    }
 }
 
 impl Default for SolidStyle {
@@ -1,24 +1,159 @@
 use crate::math::color::PINK;
+use crate::renderer::node::Junction;
 use crate::renderer::style::aab::BoxStyle;
 use crate::tilemap::NodeId;
 use crate::utils::resource::Resource;
 
+/// The [`node styles`](crate::renderer::style::node::NodeStyle) of a wall style for each [`Junction`].
+///
+/// A straight junction is only visible between different wall styles & uses the node of the end.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct JunctionNodes {
+    end: NodeId,
+    corner: NodeId,
+    t_junction: NodeId,
+    cross: NodeId,
+}
+
+impl JunctionNodes {
+    pub fn new(end: NodeId, corner: NodeId, t_junction: NodeId, cross: NodeId) -> Self {
+        JunctionNodes {
+            end,
+            corner,
+            t_junction,
+            cross,
+        }
+    }
+
+    /// Uses the same node style for all junctions.
+    pub fn uniform(node: NodeId) -> Self {
+        Self::new(node, node, node, node)
+    }
+
+    /// Returns the node style of a junction.
+    ///
+    /// ```
+    ///# use tilemap::renderer::node::Junction;
+    ///# use tilemap::renderer::style::wall::JunctionNodes;
+    ///# use tilemap::tilemap::NodeId;
+    /// let nodes = JunctionNodes::new(NodeId(0), NodeId(1), NodeId(2), NodeId(3));
+    ///
+    /// assert_eq!(nodes.get(Junction::Straight), NodeId(0));
+    /// assert_eq!(nodes.get(Junction::TJunction), NodeId(2));
+    /// ```
+    pub fn get(&self, junction: Junction) -> NodeId {
+        match junction {
+            Junction::End | Junction::Straight => self.end,
+            Junction::Corner => self.corner,
+            Junction::TJunction => self.t_junction,
+            Junction::Cross => self.cross,
+        }
+    }
+}
+
+/// How the walls are joined at a node, where the walls of a style dominate.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum WallJoin {
+    /// Covers the node with the node style of its [`Junction`].
+    #[default]
+    Node,
+    /// Fills the gap between the walls with a box as wide & deep as the thickest walls.
+    Filled,
+    /// Joins 2 walls at a corner along a diagonal seam, like the frame of a picture.
+    /// Other junctions are filled.
+    Mitered,
+}
+
+/// Where a wall is placed relative to its [`border`](crate::tilemap::border::Border) between 2 tiles.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum WallAlignment {
+    /// The wall is centered on the border & covers both tiles.
+    #[default]
+    Centered,
+    /// The wall is inside the tile at the back of a horizontal border or left of a vertical one.
+    BackLeft,
+    /// The wall is inside the tile at the front of a horizontal border or right of a vertical one.
+    FrontRight,
+}
+
 /// Defines how to render a [`wall`](crate::tilemap::border::Border::Wall).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct WallStyle {
     name: String,
     aab: BoxStyle,
-    node: NodeId,
+    nodes: JunctionNodes,
+    join: WallJoin,
+    alignment: WallAlignment,
     thickness: u32,
+    height: Option<u32>,
 }
 
 impl WallStyle {
+    /// Creates a style with the same node style at all junctions & the tile height of the views.
     pub fn new<S: Into<String>>(name: S, aab: BoxStyle, node: NodeId, thickness: u32) -> Self {
+        Self::with_junctions(name, aab, JunctionNodes::uniform(node), thickness, None)
+    }
+
+    /// Creates a style with its own height, e.g. for a low hedge or a tall palisade.
+    pub fn with_height<S: Into<String>>(
+        name: S,
+        aab: BoxStyle,
+        node: NodeId,
+        thickness: u32,
+        height: u32,
+    ) -> Self {
+        Self::with_junctions(
+            name,
+            aab,
+            JunctionNodes::uniform(node),
+            thickness,
+            Some(height),
+        )
+    }
+
+    /// Creates a style with a node style per [`Junction`] & an optional height.
+    pub fn with_junctions<S: Into<String>>(
+        name: S,
+        aab: BoxStyle,
+        nodes: JunctionNodes,
+        thickness: u32,
+        height: Option<u32>,
+    ) -> Self {
         WallStyle {
             name: name.into(),
             aab,
-            node,
+            nodes,
+            join: WallJoin::Node,
+            alignment: WallAlignment::Centered,
             thickness,
+            height,
+        }
+    }
+
+    /// Returns a copy, that joins the walls without its node styles.
+    ///
+    /// ```
+    ///# use tilemap::math::color::RED;
+    ///# use tilemap::renderer::style::aab::BoxStyle;
+    ///# use tilemap::renderer::style::wall::{WallJoin, WallStyle};
+    ///# use tilemap::tilemap::NodeId;
+    /// let style = WallStyle::new("wall", BoxStyle::shaded(RED), NodeId(0), 10);
+    ///
+    /// assert_eq!(style.get_join(), WallJoin::Node);
+    /// assert_eq!(style.joined(WallJoin::Mitered).get_join(), WallJoin::Mitered);
+    /// ```
+    pub fn joined(&self, join: WallJoin) -> Self {
+        WallStyle {
+            join,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy, that places the walls on one side of the border.
+    pub fn aligned(&self, alignment: WallAlignment) -> Self {
+        WallStyle {
+            alignment,
+            ..self.clone()
         }
     }
 
@@ -26,18 +161,71 @@ impl WallStyle {
         &self.aab
     }
 
-    pub fn get_node_style(&self) -> NodeId {
-        self.node
+    /// Returns the node style, where the walls of this style dominate a [`Junction`].
+    pub fn get_node_style(&self, junction: Junction) -> NodeId {
+        self.nodes.get(junction)
+    }
+
+    pub fn get_junction_nodes(&self) -> &JunctionNodes {
+        &self.nodes
+    }
+
+    pub fn get_join(&self) -> WallJoin {
+        self.join
     }
 
     pub fn get_thickness(&self) -> u32 {
         self.thickness
     }
+
+    pub fn get_alignment(&self) -> WallAlignment {
+        self.alignment
+    }
+
+    /// Returns the offset of the back or left face of a wall from its border.
+    ///
+    /// ```
+    ///# use tilemap::math::color::RED;
+    ///# use tilemap::renderer::style::aab::BoxStyle;
+    ///# use tilemap::renderer::style::wall::{WallAlignment, WallStyle};
+    ///# use tilemap::tilemap::NodeId;
+    /// let style = WallStyle::new("wall", BoxStyle::shaded(RED), NodeId(0), 10);
+    ///
+    /// assert_eq!(style.get_start_offset(), -5.0);
+    /// assert_eq!(style.aligned(WallAlignment::BackLeft).get_start_offset(), -10.0);
+    /// assert_eq!(style.aligned(WallAlignment::FrontRight).get_start_offset(), 0.0);
+    /// ```
+    pub fn get_start_offset(&self) -> f32 {
+        let thickness = self.thickness as f32;
+
+        match self.alignment {
+            WallAlignment::Centered => -thickness / 2.0,
+            WallAlignment::BackLeft => -thickness,
+            WallAlignment::FrontRight => 0.0,
+        }
+    }
+
+    /// Returns the offset of the center of a wall from its border in whole units,
+    /// which moves the nodes dominated by this style.
+    pub fn get_center_offset(&self) -> i32 {
+        let half = (self.thickness / 2) as i32;
+
+        match self.alignment {
+            WallAlignment::Centered => 0,
+            WallAlignment::BackLeft => -half,
+            WallAlignment::FrontRight => half,
+        }
+    }
+
+    /// Returns the height in the 3d views, if it differs from their tile height.
+    pub fn get_height(&self) -> Option<u32> {
+        self.height
+    }
 }
 
 impl Default for WallStyle {
     fn default() -> Self {
-        Self::new("default", BoxStyle::shaded(PINK), 0, 1)
+        Self::new("default", BoxStyle::shaded(PINK), NodeId(0), 1)
     }
 }
 
@@ -0,0 +1,1630 @@
+use crate::math::color::Color;
+use crate::math::point2d::Point2d;
+use crate::math::side::Side;
+use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
+use crate::port::renderer::Renderer;
+use crate::port::style::{Face, StyleKind, StyleRef};
+use crate::renderer::border::{calculate_horizontal_border, calculate_vertical_border};
+use crate::renderer::light::LightMap;
+use crate::renderer::node::{calculate_node_styles, Node};
+use crate::renderer::shadow::{clip_polygon, GroundPoint, Sun};
+use crate::renderer::style::aab::BoxStyle;
+use crate::renderer::style::floor::FloorStyle;
+use crate::renderer::style::grid::GridPosition;
+use crate::renderer::style::node::NodeShape;
+use crate::renderer::style::StyleMgr;
+use crate::renderer::view::{
+    apply_visibility, get_label_offset, render_face_outline, render_grid_at, render_grid_style,
+    View,
+};
+use crate::renderer::viewport::Viewport;
+use crate::tilemap::border::{
+    get_horizontal_borders_size, get_tile_of_horizontal_border, get_tile_of_vertical_border,
+    get_vertical_borders_size, Border,
+};
+use crate::tilemap::node::get_nodes_size;
+use crate::tilemap::selection::Selection;
+use crate::tilemap::tile::Tile;
+use crate::tilemap::tilemap2d::Tilemap2d;
+use crate::tilemap::visibility::VisibilityMask;
+use crate::utils::resource::Resource;
+use std::f32::consts::FRAC_1_SQRT_2;
+use std::ops::Range;
+
+/// A 2d vector of the rendered image.
+pub type Vector2d = (f32, f32);
+
+/// A [parallel projection](https://en.wikipedia.org/wiki/Parallel_projection),
+/// that maps the 3 axes of the tilemap onto the rendered image.
+///
+/// The x-axis follows a row to the right, the y-axis a column to the front & the z-axis points up.
+/// Each axis is the offset in the image per unit, where a tile is as wide as the tile size of the view.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Projection {
+    x_axis: Vector2d,
+    y_axis: Vector2d,
+    z_axis: Vector2d,
+}
+
+impl Projection {
+    pub fn new(x_axis: Vector2d, y_axis: Vector2d, z_axis: Vector2d) -> Self {
+        Projection {
+            x_axis,
+            y_axis,
+            z_axis,
+        }
+    }
+
+    /// The isometric projection of pixel art, where the ground axes have a slope of 1:2.
+    pub fn isometric() -> Self {
+        let f = 1.0 / 5.0_f32.sqrt();
+        Self::new((2.0 * f, f), (-2.0 * f, f), (0.0, -1.0))
+    }
+
+    /// The [military projection](https://en.wikipedia.org/wiki/Oblique_projection#Military_projection)
+    /// rotates the undistorted ground by 45° & draws heights vertically.
+    pub fn military() -> Self {
+        let f = FRAC_1_SQRT_2;
+        Self::new((f, f), (-f, f), (0.0, -1.0))
+    }
+
+    /// The [cabinet projection](https://en.wikipedia.org/wiki/Oblique_projection#Cabinet_projection)
+    /// shows the front faces undistorted & the depth at half its length with an angle of 45°.
+    pub fn cabinet() -> Self {
+        let f = FRAC_1_SQRT_2 / 2.0;
+        Self::new((1.0, 0.0), (-f, f), (0.0, -1.0))
+    }
+
+    /// A top-down view with visible front faces, like a [`ThreeFourView`](crate::renderer::view::three_four::ThreeFourView).
+    /// The depth is the ratio between the height & width of a tile.
+    pub fn three_four(depth: f32) -> Self {
+        Self::new((1.0, 0.0), (0.0, depth), (0.0, -1.0))
+    }
+
+    pub fn get_x_axis(&self) -> Vector2d {
+        self.x_axis
+    }
+
+    pub fn get_y_axis(&self) -> Vector2d {
+        self.y_axis
+    }
+
+    pub fn get_z_axis(&self) -> Vector2d {
+        self.z_axis
+    }
+
+    /// Projects a point of the tilemap onto the image.
+    ///
+    /// ```
+    ///# use tilemap::renderer::view::axonometric::Projection;
+    /// let projection = Projection::three_four(0.5);
+    ///
+    /// assert_eq!(projection.project(10.0, 20.0, 5.0), (10.0, 5.0));
+    /// ```
+    pub fn project(&self, x: f32, y: f32, z: f32) -> Vector2d {
+        (
+            x * self.x_axis.0 + y * self.y_axis.0 + z * self.z_axis.0,
+            x * self.x_axis.1 + y * self.y_axis.1 + z * self.z_axis.1,
+        )
+    }
+
+    /// Rotates the tilemap in 90 degree steps, so that the camera looks in the direction of the [`Orientation`].
+    ///
+    /// ```
+    ///# use tilemap::renderer::view::axonometric::{Orientation, Projection};
+    /// let projection = Projection::three_four(0.5);
+    /// let rotated = projection.rotate(Orientation::East);
+    ///
+    /// assert_eq!(rotated.get_x_axis(), (0.0, -0.5));
+    /// assert_eq!(rotated.get_y_axis(), (1.0, 0.0));
+    /// ```
+    pub fn rotate(&self, orientation: Orientation) -> Projection {
+        let x = self.x_axis;
+        let y = self.y_axis;
+        let (x_axis, y_axis) = match orientation {
+            Orientation::North => (x, y),
+            Orientation::East => ((-y.0, -y.1), x),
+            Orientation::South => ((-x.0, -x.1), (-y.0, -y.1)),
+            Orientation::West => (y, (-x.0, -x.1)),
+        };
+
+        Projection::new(x_axis, y_axis, self.z_axis)
+    }
+
+    /// Returns the direction towards the viewer, which is the only one projected onto a single point.
+    fn get_view_direction(&self) -> (f32, f32, f32) {
+        (
+            determinant(self.y_axis, self.z_axis),
+            determinant(self.z_axis, self.x_axis),
+            determinant(self.x_axis, self.y_axis),
+        )
+    }
+}
+
+/// The direction the camera is looking at.
+/// The default is north, where the back of the tilemap is at the top of the image.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Orientation {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Orientation {
+    /// Are the sides of the tilemap at the top & bottom of the image?
+    fn is_sideways(&self) -> bool {
+        matches!(self, Orientation::East | Orientation::West)
+    }
+}
+
+/// Lowers the walls in front of floors, so they don't hide the floor behind them.
+///
+/// A wall is lowered, if one of its long faces is visible & the tile behind it, seen from the camera, is a floor.
+/// So the back walls of a room stay at full height, while its front walls are lowered.
+/// The nodes connected to lowered walls & optionally the solids are lowered too.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cutaway {
+    height: u32,
+    solids: bool,
+}
+
+impl Cutaway {
+    pub fn new(height: u32, solids: bool) -> Self {
+        Cutaway { height, solids }
+    }
+
+    /// Only renders the top face of the lowered walls on the floor.
+    pub fn footprint(solids: bool) -> Self {
+        Self::new(0, solids)
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Are the solids lowered too?
+    pub fn includes_solids(&self) -> bool {
+        self.solids
+    }
+}
+
+/// The determinant is positive, if b is clockwise from a in the image.
+fn determinant(a: Vector2d, b: Vector2d) -> f32 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+/// An axis aligned box in the coordinates of the tilemap.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Cuboid {
+    x: f32,
+    y: f32,
+    width: f32,
+    depth: f32,
+    height: f32,
+}
+
+/// A box of the tilemap, that is rendered with the painter's algorithm.
+struct Element<'a> {
+    selection: Selection,
+    layer: Layer,
+    style_ref: StyleRef<'a>,
+    style: BoxStyle,
+    cuboid: Cuboid,
+    /// The corners of a prism inside the box, e.g. for a round node, that replaces the box.
+    footprint: Option<Vec<GroundPoint>>,
+}
+
+/// Renders a [`Tilemap2d`](crate::tilemap::tilemap2d::Tilemap2d) with any [`Projection`],
+/// e.g. an [axonometric](https://en.wikipedia.org/wiki/Axonometric_projection)
+/// or [oblique](https://en.wikipedia.org/wiki/Oblique_projection) one.
+pub struct AxonometricView {
+    projection: Projection,
+    orientation: Orientation,
+    /// The projection rotated by the orientation.
+    camera: Projection,
+    tile_size: u32,
+    /// The height of styles without their own height.
+    tile_height: u32,
+    /// The space reserved on the ground for the labels of the grid at the left & back edge.
+    label_margin: u32,
+    sun: Option<Sun>,
+    cutaway: Option<Cutaway>,
+}
+
+impl View for AxonometricView {
+    fn get_size(&self, tiles: Size2d, styles: &StyleMgr) -> Size2d {
+        let (min, max) = self.get_map_bounding_box(tiles, self.get_reserved_height(styles));
+        Size2d::new(
+            (max.0 - min.0).round() as u32,
+            (max.1 - min.1).round() as u32,
+        )
+    }
+
+    fn get_tile_viewport(
+        &self,
+        tiles: Size2d,
+        start: Point2d,
+        size: Size2d,
+        styles: &StyleMgr,
+    ) -> Viewport {
+        let height = self.get_reserved_height(styles);
+        let origin = self.get_origin(tiles, height);
+        let (min, max) = self.get_bounding_box(start, size, height);
+        let start = self.to_image(origin, min);
+        let end = self.to_image(origin, max);
+
+        Viewport::new(
+            start,
+            Size2d::new((end.x - start.x) as u32, (end.y - start.y) as u32),
+        )
+    }
+
+    fn render(
+        &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+    ) {
+        let Some((hidden, lights)) = apply_visibility(tilemap, visibility) else {
+            return;
+        };
+        let tilemap = hidden.as_ref().unwrap_or(tilemap);
+        let tiles = tilemap.get_size();
+        let origin = self.get_origin(tiles, self.get_reserved_height(styles));
+
+        self.render_floors(tilemap, &lights, origin, renderer, styles);
+
+        let nodes = calculate_node_styles(styles, tilemap);
+        let elements = self.collect_elements(tilemap, &lights, &nodes, styles);
+
+        if let Some(sun) = &self.sun {
+            self.render_shadows(tilemap, &elements, origin, renderer, sun);
+        }
+
+        render_grid_at(self, GridPosition::BelowWalls, tiles, renderer, styles);
+
+        for element in &elements {
+            renderer.start_selection(element.selection);
+            renderer.set_layer(element.layer);
+
+            match &element.footprint {
+                None => self.render_box(
+                    renderer,
+                    origin,
+                    &element.cuboid,
+                    &element.style,
+                    element.style_ref,
+                ),
+                Some(corners) => self.render_prism(
+                    renderer,
+                    origin,
+                    corners,
+                    element.cuboid.height,
+                    &element.style,
+                    element.style_ref,
+                ),
+            }
+        }
+
+        render_grid_at(self, GridPosition::AboveWalls, tiles, renderer, styles);
+    }
+
+    fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
+        let origin = self.get_origin(tiles, self.get_reserved_height(styles));
+        let tile_size = self.tile_size as f32;
+
+        render_grid_style(
+            tiles,
+            renderer,
+            styles.get_grid_style(),
+            self.tile_size / 4,
+            get_label_offset(self.label_margin, Size2d::square(self.tile_size)),
+            |x, y| {
+                self.to_image(
+                    origin,
+                    self.camera.project(x * tile_size, y * tile_size, 0.0),
+                )
+            },
+        );
+    }
+}
+
+impl AxonometricView {
+    pub fn new(projection: Projection, tile_size: u32, tile_height: u32) -> Self {
+        AxonometricView {
+            projection,
+            orientation: Orientation::default(),
+            camera: projection,
+            tile_size,
+            tile_height,
+            label_margin: 0,
+            sun: None,
+            cutaway: None,
+        }
+    }
+
+    /// Creates a view, where the [`Sun`] casts the shadows of solids, walls & nodes onto the floor.
+    pub fn with_sun(projection: Projection, tile_size: u32, tile_height: u32, sun: Sun) -> Self {
+        AxonometricView {
+            sun: Some(sun),
+            ..Self::new(projection, tile_size, tile_height)
+        }
+    }
+
+    pub fn get_projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    pub fn get_orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Rotates the camera around the tilemap without changing it.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+        self.camera = self.projection.rotate(orientation);
+    }
+
+    pub fn get_cutaway(&self) -> Option<Cutaway> {
+        self.cutaway
+    }
+
+    /// Lowers the walls in front of floors or renders all walls at full height.
+    pub fn set_cutaway(&mut self, cutaway: Option<Cutaway>) {
+        self.cutaway = cutaway;
+    }
+
+    pub fn get_label_margin(&self) -> u32 {
+        self.label_margin
+    }
+
+    /// Reserves space in the image at the left & back edge of the tilemap for the labels of the grid.
+    /// Without it, the labels are rendered inside the tiles at the edge.
+    pub fn set_label_margin(&mut self, margin: u32) {
+        self.label_margin = margin;
+    }
+
+    /// Renders all floor tiles first, because everything else is rendered on top of them.
+    fn render_floors(
+        &self,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        origin: Vector2d,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+    ) {
+        let tiles = tilemap.get_size();
+        let tile_size = self.tile_size as f32;
+
+        renderer.set_layer(Layer::Floors);
+
+        for index in 0..tiles.count() {
+            if let Tile::Floor(id) = tilemap.get_tile(index) {
+                let style = styles.get_floor_style(id);
+                let x = tiles.to_x(index) as f32 * tile_size;
+                let y = tiles.to_y(index) as f32 * tile_size;
+                let points = [
+                    self.project(origin, x, y, 0.0),
+                    self.project(origin, x, y + tile_size, 0.0),
+                    self.project(origin, x + tile_size, y + tile_size, 0.0),
+                    self.project(origin, x + tile_size, y, 0.0),
+                ];
+
+                let tile = tiles.to_point(index);
+
+                renderer.start_selection(Selection::Tile(index));
+                renderer.set_style(StyleRef::new(StyleKind::Floor, style.get_name()));
+                render_floor(renderer, points, style, tile, lights.get_tile_light(index));
+            }
+        }
+    }
+
+    /// Collects the solids, nodes & walls in the order of the painter's algorithm.
+    ///
+    /// The tilemap is traversed from the back to the front as seen by the viewer.
+    /// Each cell contains a tile, the node at its far corner & the borders on its 2 far sides.
+    /// An additional row & column of cells outside the tilemap contains the borders & nodes on the near edges.
+    fn collect_elements<'a>(
+        &self,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        nodes: &[Node<'a>],
+        styles: &'a StyleMgr,
+    ) -> Vec<Element<'a>> {
+        let tiles = tilemap.get_size();
+        let (dx, dy, _) = self.camera.get_view_direction();
+        let step_x = if dx < 0.0 { -1 } else { 1 };
+        let step_y = if dy < 0.0 { -1 } else { 1 };
+        let offset_x = (step_x < 0) as i32;
+        let offset_y = (step_y < 0) as i32;
+        let columns = get_cells(tiles.width() as i32, step_x);
+        let rows = get_cells(tiles.height() as i32, step_y);
+        let mut elements = Vec::new();
+
+        let mut add_cell = |x: i32, y: i32| {
+            self.add_solid(&mut elements, tilemap, lights, styles, x, y);
+            self.add_node(
+                &mut elements,
+                tilemap,
+                lights,
+                nodes,
+                styles,
+                x + offset_x,
+                y + offset_y,
+            );
+            self.add_horizontal_wall(
+                &mut elements,
+                tilemap,
+                lights,
+                nodes,
+                styles,
+                x,
+                y + offset_y,
+            );
+            self.add_vertical_wall(
+                &mut elements,
+                tilemap,
+                lights,
+                nodes,
+                styles,
+                x + offset_x,
+                y,
+            );
+        };
+
+        if dy.abs() >= dx.abs() {
+            for y in &rows {
+                for x in &columns {
+                    add_cell(*x, *y);
+                }
+            }
+        } else {
+            for x in &columns {
+                for y in &rows {
+                    add_cell(*x, *y);
+                }
+            }
+        }
+
+        elements
+    }
+
+    fn add_solid<'a>(
+        &self,
+        elements: &mut Vec<Element<'a>>,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        styles: &'a StyleMgr,
+        x: i32,
+        y: i32,
+    ) {
+        let tiles = tilemap.get_size();
+
+        if !is_inside(tiles, x, y) {
+            return;
+        }
+
+        let index = tiles.convert_x_y(x as u32, y as u32);
+
+        if let Tile::Solid(id) = tilemap.get_tile(index) {
+            let style = styles.get_solid_style(id);
+            let tile_size = self.tile_size as f32;
+            let (front, side) = self.get_visible_sides();
+
+            elements.push(Element {
+                selection: Selection::Tile(index),
+                layer: Layer::Solids,
+                style_ref: StyleRef::new(StyleKind::Solid, style.get_name()),
+                style: lights
+                    .get_solid_lights_facing(index, front, side)
+                    .illuminate(&style.get_tile_style(x as u32, y as u32).facing(front, side)),
+                cuboid: Cuboid {
+                    x: x as f32 * tile_size,
+                    y: y as f32 * tile_size,
+                    width: tile_size,
+                    depth: tile_size,
+                    height: self.get_solid_height(style.get_height()),
+                },
+                footprint: None,
+            });
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_node<'a>(
+        &self,
+        elements: &mut Vec<Element<'a>>,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        nodes: &[Node<'a>],
+        styles: &StyleMgr,
+        x: i32,
+        y: i32,
+    ) {
+        let nodes_size = get_nodes_size(tilemap.get_size());
+
+        if !is_inside(nodes_size, x, y) {
+            return;
+        }
+
+        let index = nodes_size.convert_x_y(x as u32, y as u32);
+        let tile_size = self.tile_size as f32;
+        let center = (x as f32 * tile_size, y as f32 * tile_size);
+        let (front, side) = self.get_visible_sides();
+
+        match &nodes[index] {
+            Node::NoNode | Node::InnerNode => {}
+            Node::OuterNode(style, offset) => {
+                let center = (center.0 + offset.x as f32, center.1 + offset.y as f32);
+                let style_ref = StyleRef::new(StyleKind::Node, style.get_name());
+                let aab = lights
+                    .get_node_lights(index)
+                    .illuminate(&style.get_style().facing(front, side));
+                let height = self.get_node_height(tilemap, styles, style.get_height(), x, y);
+
+                let element = match style.get_shape() {
+                    NodeShape::Square => {
+                        let half = style.get_half() as f32;
+                        let size = style.get_size() as f32;
+
+                        Element {
+                            selection: Selection::Node(index),
+                            layer: Layer::Nodes,
+                            style_ref,
+                            style: aab,
+                            cuboid: Cuboid {
+                                x: center.0 - half,
+                                y: center.1 - half,
+                                width: size,
+                                depth: size,
+                                height,
+                            },
+                            footprint: None,
+                        }
+                    }
+                    shape => {
+                        let corners = shape.get_corners(style.get_size());
+                        create_prism(index, style_ref, aab, center, &corners, height)
+                    }
+                };
+
+                elements.push(element);
+            }
+            Node::Joint(joint) => {
+                for part in joint.get_parts() {
+                    let style = part.get_style();
+                    let style_ref = StyleRef::new(StyleKind::Wall, style.get_name());
+                    let aab = lights
+                        .get_node_lights(index)
+                        .illuminate(&style.get_aab_style().facing(front, side));
+                    let is_lowered = match part.get_side() {
+                        None => false,
+                        Some(Side::Left) => self.is_hiding_floor(tilemap, true, x - 1, y),
+                        Some(Side::Right) => self.is_hiding_floor(tilemap, true, x, y),
+                        Some(Side::Back) => self.is_hiding_floor(tilemap, false, x, y - 1),
+                        Some(Side::Front) => self.is_hiding_floor(tilemap, false, x, y),
+                    };
+                    let height = match part.get_side() {
+                        None => self.get_node_height(tilemap, styles, None, x, y),
+                        Some(_) => self.get_wall_height(style.get_height(), is_lowered),
+                    };
+
+                    elements.push(create_prism(
+                        index,
+                        style_ref,
+                        aab,
+                        center,
+                        part.get_corners(),
+                        height,
+                    ));
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_horizontal_wall<'a>(
+        &self,
+        elements: &mut Vec<Element<'a>>,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        nodes: &[Node],
+        styles: &'a StyleMgr,
+        x: i32,
+        y: i32,
+    ) {
+        let tiles = tilemap.get_size();
+        let borders_size = get_horizontal_borders_size(tiles);
+
+        if !is_inside(borders_size, x, y) {
+            return;
+        }
+
+        let border_index = borders_size.convert_x_y(x as u32, y as u32);
+
+        if let Border::Wall(id) = tilemap.get_horizontal_borders()[border_index] {
+            let style = styles.get_wall_style(id);
+            let thickness = style.get_thickness() as f32;
+            let (start, length) =
+                calculate_horizontal_border(nodes, self.tile_size, border_index, y as u32);
+            let (tile_index, side) = get_tile_of_horizontal_border(tiles, border_index);
+            let (front, side_face) = self.get_visible_sides();
+            let is_lowered = self.is_hiding_floor(tilemap, true, x, y);
+            let tile_size = self.tile_size as f32;
+
+            elements.push(Element {
+                selection: Selection::Border(tile_index, side),
+                layer: Layer::Walls,
+                style_ref: StyleRef::new(StyleKind::Wall, style.get_name()),
+                style: lights
+                    .get_wall_lights_facing(tile_index, side, front, side_face)
+                    .illuminate(&style.get_aab_style().facing(front, side_face)),
+                cuboid: Cuboid {
+                    x: x as f32 * tile_size + start as f32,
+                    y: y as f32 * tile_size + style.get_start_offset(),
+                    width: length as f32,
+                    depth: thickness,
+                    height: self.get_wall_height(style.get_height(), is_lowered),
+                },
+                footprint: None,
+            });
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_vertical_wall<'a>(
+        &self,
+        elements: &mut Vec<Element<'a>>,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        nodes: &[Node],
+        styles: &'a StyleMgr,
+        x: i32,
+        y: i32,
+    ) {
+        let tiles = tilemap.get_size();
+        let borders_size = get_vertical_borders_size(tiles);
+
+        if !is_inside(borders_size, x, y) {
+            return;
+        }
+
+        let border_index = borders_size.convert_x_y(x as u32, y as u32);
+
+        if let Border::Wall(id) = tilemap.get_vertical_borders()[border_index] {
+            let style = styles.get_wall_style(id);
+            let thickness = style.get_thickness() as f32;
+            let (start, length) =
+                calculate_vertical_border(nodes, self.tile_size, borders_size, border_index);
+            let (tile_index, side) = get_tile_of_vertical_border(tiles, border_index);
+            let (front, side_face) = self.get_visible_sides();
+            let is_lowered = self.is_hiding_floor(tilemap, false, x, y);
+            let tile_size = self.tile_size as f32;
+
+            elements.push(Element {
+                selection: Selection::Border(tile_index, side),
+                layer: Layer::Walls,
+                style_ref: StyleRef::new(StyleKind::Wall, style.get_name()),
+                style: lights
+                    .get_wall_lights_facing(tile_index, side, front, side_face)
+                    .illuminate(&style.get_aab_style().facing(front, side_face)),
+                cuboid: Cuboid {
+                    x: x as f32 * tile_size + style.get_start_offset(),
+                    y: y as f32 * tile_size + start as f32,
+                    width: thickness,
+                    depth: length as f32,
+                    height: self.get_wall_height(style.get_height(), is_lowered),
+                },
+                footprint: None,
+            });
+        }
+    }
+
+    /// Renders the shadows of all solids, walls & nodes before any of them.
+    /// The shadows are clipped to the floor tiles, so they don't darken empty tiles.
+    fn render_shadows(
+        &self,
+        tilemap: &Tilemap2d,
+        elements: &[Element],
+        origin: Vector2d,
+        renderer: &mut dyn Renderer,
+        sun: &Sun,
+    ) {
+        let tiles = tilemap.get_size();
+        let tile_size = self.tile_size as f32;
+        let mut polygons: Vec<Vec<GroundPoint>> = Vec::new();
+
+        for element in elements {
+            let cuboid = &element.cuboid;
+
+            if cuboid.height <= 0.0 {
+                continue;
+            }
+
+            match &element.footprint {
+                None => polygons.extend(
+                    sun.calculate_box_shadow(
+                        (cuboid.x, cuboid.y),
+                        (cuboid.width, cuboid.depth),
+                        cuboid.height,
+                    )
+                    .iter()
+                    .map(|quad| quad.to_vec()),
+                ),
+                Some(corners) => {
+                    polygons.extend(sun.calculate_prism_shadow(corners, cuboid.height))
+                }
+            }
+        }
+
+        renderer.set_layer(Layer::Shadows);
+        renderer.set_style(StyleRef::new(StyleKind::Shadow, "sun"));
+
+        for polygon in &polygons {
+            let Some((columns, rows)) = get_overlapped_tiles(polygon, tiles, tile_size) else {
+                continue;
+            };
+
+            for y in rows {
+                for x in columns.clone() {
+                    let index = tiles.convert_x_y(x, y);
+
+                    if !matches!(tilemap.get_tile(index), Tile::Floor(_)) {
+                        continue;
+                    }
+
+                    let start = (x as f32 * tile_size, y as f32 * tile_size);
+                    let end = (start.0 + tile_size, start.1 + tile_size);
+                    let clipped = clip_polygon(polygon, start, end);
+
+                    if clipped.is_empty() {
+                        continue;
+                    }
+
+                    let points: Vec<Point2d> = clipped
+                        .iter()
+                        .map(|(x, y)| self.project(origin, *x, *y, 0.0))
+                        .collect();
+                    renderer.render_shadow(&points, sun.get_color(), sun.get_opacity());
+                }
+            }
+        }
+    }
+
+    /// Renders the faces of an axis aligned box, that face the viewer.
+    fn render_box(
+        &self,
+        renderer: &mut dyn Renderer,
+        origin: Vector2d,
+        cuboid: &Cuboid,
+        style: &BoxStyle,
+        style_ref: StyleRef,
+    ) {
+        let (dx, dy, dz) = self.camera.get_view_direction();
+        let x0 = cuboid.x;
+        let y0 = cuboid.y;
+        let x1 = x0 + cuboid.width;
+        let y1 = y0 + cuboid.depth;
+        let z = cuboid.height;
+        let point = |x, y, z| self.project(origin, x, y, z);
+
+        let mut faces = Vec::new();
+
+        if dz > 0.0 {
+            let top = [
+                point(x0, y0, z),
+                point(x0, y1, z),
+                point(x1, y1, z),
+                point(x1, y0, z),
+            ];
+            faces.push((
+                Face::Top,
+                top,
+                *style.get_top_color(),
+                style.get_top_image(),
+            ));
+        }
+
+        if z > 0.0 {
+            let y = if dy > 0.0 { y1 } else { y0 };
+            let y_face = (
+                dy,
+                order_vertical_face(
+                    point(x0, y, z),
+                    point(x0, y, 0.0),
+                    point(x1, y, 0.0),
+                    point(x1, y, z),
+                ),
+            );
+            let x = if dx > 0.0 { x1 } else { x0 };
+            let x_face = (
+                dx,
+                order_vertical_face(
+                    point(x, y0, z),
+                    point(x, y0, 0.0),
+                    point(x, y1, 0.0),
+                    point(x, y1, z),
+                ),
+            );
+            let (front, side) = if self.orientation.is_sideways() {
+                (x_face, y_face)
+            } else {
+                (y_face, x_face)
+            };
+
+            if front.0 != 0.0 {
+                let color = *style.get_front_color();
+                faces.push((Face::Front, front.1, color, style.get_side_image()));
+            }
+
+            if side.0 != 0.0 {
+                let color = *style.get_side_color();
+                faces.push((Face::Side, side.1, color, style.get_side_image()));
+            }
+        }
+
+        for (face, points, color, image) in &faces {
+            renderer.set_style(style_ref.with_face(*face));
+
+            match image {
+                None => render_quad(renderer, *points, *color),
+                Some(image) => {
+                    let [p0, p1, p2, p3] = *points;
+                    renderer.render_image(p0, p1, p2, p3, image, *color)
+                }
+            }
+        }
+
+        // the outlines are rendered last, so the faces don't cover them
+        for (_face, points, _color, _image) in &faces {
+            render_face_outline(renderer, points, style);
+        }
+    }
+
+    /// Renders the faces of a prism, that face the viewer.
+    /// The color of each side face blends the front & side colors by its direction.
+    fn render_prism(
+        &self,
+        renderer: &mut dyn Renderer,
+        origin: Vector2d,
+        corners: &[GroundPoint],
+        z: f32,
+        style: &BoxStyle,
+        style_ref: StyleRef,
+    ) {
+        let (dx, dy, dz) = self.camera.get_view_direction();
+        let front_x = if dx < 0.0 { -1.0 } else { 1.0 };
+        let front_y = if dy < 0.0 { -1.0 } else { 1.0 };
+        let (front_axis, side_axis) = if self.orientation.is_sideways() {
+            ((front_x, 0.0), (0.0, front_y))
+        } else {
+            ((0.0, front_y), (front_x, 0.0))
+        };
+
+        let mut faces = Vec::new();
+
+        if z > 0.0 {
+            for (index, start) in corners.iter().enumerate() {
+                let end = corners[(index + 1) % corners.len()];
+                let normal = (start.1 - end.1, end.0 - start.0);
+
+                if get_share(normal, (dx, dy)) == 0.0 {
+                    continue;
+                }
+
+                let front = get_share(normal, front_axis);
+                let side = get_share(normal, side_axis);
+                let weight = front / (front + side);
+                let color =
+                    *style.get_front_color() * weight + *style.get_side_color() * (1.0 - weight);
+                let face = if weight >= 0.5 {
+                    Face::Front
+                } else {
+                    Face::Side
+                };
+                let points = vec![
+                    self.project(origin, start.0, start.1, z),
+                    self.project(origin, start.0, start.1, 0.0),
+                    self.project(origin, end.0, end.1, 0.0),
+                    self.project(origin, end.0, end.1, z),
+                ];
+
+                faces.push((face, points, color));
+            }
+        }
+
+        if dz > 0.0 {
+            let top = corners
+                .iter()
+                .map(|(x, y)| self.project(origin, *x, *y, z))
+                .collect();
+            faces.push((Face::Top, top, *style.get_top_color()));
+        }
+
+        for (face, points, color) in &faces {
+            renderer.set_style(style_ref.with_face(*face));
+            renderer.render_polygon(points, *color);
+        }
+
+        // the outlines are rendered last, so the faces don't cover them
+        for (_face, points, _color) in &faces {
+            render_face_outline(renderer, points, style);
+        }
+    }
+
+    /// Returns the height of a style or the tile height, if it has none.
+    fn get_style_height(&self, height: Option<u32>) -> u32 {
+        height.unwrap_or(self.tile_height)
+    }
+
+    fn get_solid_height(&self, height: Option<u32>) -> f32 {
+        let height = self.get_style_height(height);
+
+        match self.cutaway {
+            Some(cutaway) if cutaway.includes_solids() => get_cut_height(cutaway, height),
+            _ => height as f32,
+        }
+    }
+
+    /// Returns the height of a wall, which is lowered by the [`Cutaway`], if it hides a floor.
+    fn get_wall_height(&self, height: Option<u32>, is_lowered: bool) -> f32 {
+        let height = self.get_style_height(height);
+
+        match self.cutaway {
+            Some(cutaway) if is_lowered => get_cut_height(cutaway, height),
+            _ => height as f32,
+        }
+    }
+
+    /// Does a wall hide the floor behind it, seen from the camera, so the [`Cutaway`] lowers it?
+    /// The position of the horizontal or vertical wall is in the coordinates of its borders.
+    /// Walls, whose long faces aren't visible, hide nothing.
+    fn is_hiding_floor(&self, tilemap: &Tilemap2d, is_horizontal: bool, x: i32, y: i32) -> bool {
+        let (dx, dy, _) = self.camera.get_view_direction();
+        let direction = if is_horizontal { dy } else { dx };
+
+        if direction == 0.0 {
+            return false;
+        }
+
+        let (tile_x, tile_y) = match (is_horizontal, direction > 0.0) {
+            (true, true) => (x, y - 1),
+            (false, true) => (x - 1, y),
+            _ => (x, y),
+        };
+        let tiles = tilemap.get_size();
+
+        is_inside(tiles, tile_x, tile_y)
+            && matches!(
+                tilemap.get_tile(tiles.convert_x_y(tile_x as u32, tile_y as u32)),
+                Tile::Floor(_)
+            )
+    }
+
+    /// Returns the height of a node, which is lowered with the walls hiding a floor, that it connects.
+    /// A node without its own height is as high as the highest of its walls.
+    fn get_node_height(
+        &self,
+        tilemap: &Tilemap2d,
+        styles: &StyleMgr,
+        height: Option<u32>,
+        x: i32,
+        y: i32,
+    ) -> f32 {
+        let tiles = tilemap.get_size();
+        let horizontal_size = get_horizontal_borders_size(tiles);
+        let vertical_size = get_vertical_borders_size(tiles);
+        let get_wall = |borders: &[Border], size: Size2d, x: i32, y: i32| {
+            if !is_inside(size, x, y) {
+                return None;
+            }
+
+            borders[size.convert_x_y(x as u32, y as u32)].get_wall_style()
+        };
+        let horizontal = tilemap.get_horizontal_borders();
+        let vertical = tilemap.get_vertical_borders();
+        let walls = [
+            (
+                get_wall(horizontal, horizontal_size, x - 1, y),
+                true,
+                x - 1,
+                y,
+            ),
+            (get_wall(horizontal, horizontal_size, x, y), true, x, y),
+            (get_wall(vertical, vertical_size, x, y - 1), false, x, y - 1),
+            (get_wall(vertical, vertical_size, x, y), false, x, y),
+        ];
+        let mut is_lowered = false;
+        let mut max_height = None;
+
+        for (id, is_horizontal, wall_x, wall_y) in walls {
+            if let Some(id) = id {
+                let height = self.get_style_height(styles.get_wall_style(id).get_height());
+                is_lowered |= self.is_hiding_floor(tilemap, is_horizontal, wall_x, wall_y);
+                max_height = max_height.max(Some(height));
+            }
+        }
+
+        self.get_wall_height(height.or(max_height), is_lowered)
+    }
+
+    /// Returns the sides of the tilemap, that the front & side faces of the boxes point to.
+    /// The front faces are at the bottom of the image & the side faces at the right.
+    fn get_visible_sides(&self) -> (Side, Side) {
+        let (dx, dy, _) = self.camera.get_view_direction();
+        let x_side = if dx < 0.0 { Side::Left } else { Side::Right };
+        let y_side = if dy < 0.0 { Side::Back } else { Side::Front };
+
+        if self.orientation.is_sideways() {
+            (x_side, y_side)
+        } else {
+            (y_side, x_side)
+        }
+    }
+
+    /// Returns the height reserved in the image, so the tallest style isn't clipped.
+    fn get_reserved_height(&self, styles: &StyleMgr) -> f32 {
+        self.tile_height.max(styles.get_max_height().unwrap_or(0)) as f32
+    }
+
+    /// Returns the offset, that moves the projected tilemap to the origin of the image.
+    fn get_origin(&self, tiles: Size2d, height: f32) -> Vector2d {
+        let (min, _max) = self.get_map_bounding_box(tiles, height);
+        (-min.0, -min.1)
+    }
+
+    /// Returns the bounding box of the whole tilemap & the label margin with a height in the coordinates of the projection.
+    fn get_map_bounding_box(&self, tiles: Size2d, height: f32) -> (Vector2d, Vector2d) {
+        let tile_size = self.tile_size as f32;
+        let margin = self.label_margin as f32;
+        let end = (
+            tiles.width() as f32 * tile_size,
+            tiles.height() as f32 * tile_size,
+        );
+
+        self.get_ground_bounding_box((-margin, -margin), end, height)
+    }
+
+    /// Returns the bounding box of a rectangle of tiles with a height in the coordinates of the projection.
+    fn get_bounding_box(&self, start: Point2d, size: Size2d, height: f32) -> (Vector2d, Vector2d) {
+        let tile_size = self.tile_size as f32;
+        let end = start + size;
+
+        self.get_ground_bounding_box(
+            (start.x as f32 * tile_size, start.y as f32 * tile_size),
+            (end.x as f32 * tile_size, end.y as f32 * tile_size),
+            height,
+        )
+    }
+
+    /// Returns the bounding box of a rectangle on the ground with a height in the coordinates of the projection.
+    fn get_ground_bounding_box(
+        &self,
+        start: Vector2d,
+        end: Vector2d,
+        height: f32,
+    ) -> (Vector2d, Vector2d) {
+        let x_values = [start.0, end.0];
+        let y_values = [start.1, end.1];
+        let z_values = [0.0, height];
+        let mut min = (f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN);
+
+        for x in x_values {
+            for y in y_values {
+                for z in z_values {
+                    let p = self.camera.project(x, y, z);
+                    min = (min.0.min(p.0), min.1.min(p.1));
+                    max = (max.0.max(p.0), max.1.max(p.1));
+                }
+            }
+        }
+
+        (min, max)
+    }
+
+    /// Projects a point of the tilemap onto the image.
+    fn project(&self, origin: Vector2d, x: f32, y: f32, z: f32) -> Point2d {
+        self.to_image(origin, self.camera.project(x, y, z))
+    }
+
+    fn to_image(&self, origin: Vector2d, point: Vector2d) -> Point2d {
+        Point2d::new(
+            (origin.0 + point.0).round() as i32,
+            (origin.1 + point.1).round() as i32,
+        )
+    }
+}
+
+/// Returns the height of an element lowered by the [`Cutaway`].
+fn get_cut_height(cutaway: Cutaway, height: u32) -> f32 {
+    cutaway.get_height().min(height) as f32
+}
+
+/// Creates a prism at a node from corners relative to its center.
+fn create_prism<'a>(
+    index: usize,
+    style_ref: StyleRef<'a>,
+    style: BoxStyle,
+    center: GroundPoint,
+    corners: &[(f32, f32)],
+    height: f32,
+) -> Element<'a> {
+    let corners: Vec<GroundPoint> = corners
+        .iter()
+        .map(|(x, y)| (center.0 + x, center.1 + y))
+        .collect();
+    let min_x = corners.iter().map(|c| c.0).fold(f32::MAX, f32::min);
+    let min_y = corners.iter().map(|c| c.1).fold(f32::MAX, f32::min);
+    let max_x = corners.iter().map(|c| c.0).fold(f32::MIN, f32::max);
+    let max_y = corners.iter().map(|c| c.1).fold(f32::MIN, f32::max);
+
+    Element {
+        selection: Selection::Node(index),
+        layer: Layer::Nodes,
+        style_ref,
+        style,
+        cuboid: Cuboid {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            depth: max_y - min_y,
+            height,
+        },
+        footprint: Some(corners),
+    }
+}
+
+/// Returns how much a face with a normal on the ground points along an axis or 0, if it doesn't.
+/// Ignores rounding errors, so faces along an axis are hidden or get the exact color.
+fn get_share(normal: GroundPoint, axis: Vector2d) -> f32 {
+    let share = (normal.0 * axis.0 + normal.1 * axis.1) / normal.0.hypot(normal.1);
+
+    if share < 0.001 {
+        0.0
+    } else {
+        share
+    }
+}
+
+/// Returns the cells along an axis in the order of the traversal.
+/// The cells include one outside the tilemap, that contains the borders & nodes of the near edge.
+fn get_cells(length: i32, step: i32) -> Vec<i32> {
+    if step > 0 {
+        (0..=length).collect()
+    } else {
+        (-1..length).rev().collect()
+    }
+}
+
+fn is_inside(size: Size2d, x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < size.width() as i32 && y < size.height() as i32
+}
+
+/// Returns the columns & rows of the tiles, that the bounding box of a polygon on the ground overlaps.
+fn get_overlapped_tiles(
+    polygon: &[GroundPoint],
+    tiles: Size2d,
+    tile_size: f32,
+) -> Option<(Range<u32>, Range<u32>)> {
+    let mut min = (f32::MAX, f32::MAX);
+    let mut max = (f32::MIN, f32::MIN);
+
+    for (x, y) in polygon {
+        min = (min.0.min(*x), min.1.min(*y));
+        max = (max.0.max(*x), max.1.max(*y));
+    }
+
+    let to_range = |min: f32, max: f32, count: u32| {
+        let start = (min / tile_size).floor().max(0.0) as u32;
+        let end = ((max / tile_size).ceil().max(0.0) as u32).min(count);
+        (start < end).then_some(start..end)
+    };
+
+    Some((
+        to_range(min.0, max.0, tiles.width())?,
+        to_range(min.1, max.1, tiles.height())?,
+    ))
+}
+
+/// Renders the floor tile at a position of the tilemap.
+/// The points are ordered like for [`Renderer::render_pattern()`].
+fn render_floor(
+    renderer: &mut dyn Renderer,
+    points: [Point2d; 4],
+    style: &FloorStyle,
+    tile: Point2d,
+    light: Color,
+) {
+    let color = style.get_tile_color(tile.x as u32, tile.y as u32) * light;
+    let [p0, p1, p2, p3] = points;
+
+    match (style.get_image(), style.get_pattern()) {
+        (Some(image), _) => renderer.render_image(p0, p1, p2, p3, image, color),
+        (None, Some(pattern)) => {
+            let pattern = pattern.illuminate(light);
+            renderer.render_pattern(p0, p1, p2, p3, &pattern, tile, color)
+        }
+        (None, None) => render_quad(renderer, points, color),
+    }
+}
+
+/// Orders the corners of a vertical face like for [`Renderer::render_image()`],
+/// so its image is upright & not mirrored.
+fn order_vertical_face(
+    top0: Point2d,
+    bottom0: Point2d,
+    bottom1: Point2d,
+    top1: Point2d,
+) -> [Point2d; 4] {
+    if top0.x <= top1.x {
+        [top0, bottom0, bottom1, top1]
+    } else {
+        [top1, bottom1, bottom0, top0]
+    }
+}
+
+/// Renders a quad as an axis aligned rectangle, if possible.
+fn render_quad(renderer: &mut dyn Renderer, points: [Point2d; 4], color: Color) {
+    let [p0, p1, p2, p3] = points;
+    let is_rectangle = (p0.x == p1.x && p1.y == p2.y && p2.x == p3.x && p3.y == p0.y)
+        || (p0.y == p1.y && p1.x == p2.x && p2.y == p3.y && p3.x == p0.x);
+
+    if is_rectangle {
+        let x = p0.x.min(p2.x);
+        let y = p0.y.min(p2.y);
+        let size = Size2d::new((p0.x - p2.x).unsigned_abs(), (p0.y - p2.y).unsigned_abs());
+        renderer.render_rectangle(x, y, size, color);
+    } else {
+        renderer.render_transformed_rectangle(p0, p1, p2, p3, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::{BLACK, BLUE, GREEN, RED, WHITE};
+    use crate::renderer::recorder::Recorder;
+    use crate::renderer::style::grid::GridStyle;
+    use crate::renderer::style::image::Image;
+    use crate::renderer::style::jitter::ColorJitter;
+    use crate::renderer::style::node::NodeStyle;
+    use crate::renderer::style::solid::SolidStyle;
+    use crate::renderer::style::wall::WallStyle;
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::tile::FloorId;
+    use crate::tilemap::tile::SolidId;
+    use crate::tilemap::NodeId;
+
+    #[test]
+    fn test_view_direction_of_isometric() {
+        let (x, y, z) = Projection::isometric().get_view_direction();
+
+        assert!(x > 0.0);
+        assert!(y > 0.0);
+        assert!(z > 0.0);
+    }
+
+    #[test]
+    fn test_shadows_only_darken_floors() {
+        let tiles = Size2d::new(3, 1);
+        let tilemap = Tilemap2d::new(
+            tiles,
+            vec![
+                Tile::Floor(FloorId(0)),
+                Tile::Solid(SolidId(0)),
+                Tile::Empty,
+            ],
+        )
+        .unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+        let render = |direction_x| {
+            let sun = Sun::new(direction_x, 0.0, BLACK);
+            let viewer = AxonometricView::with_sun(Projection::isometric(), 100, 100, sun);
+            let mut recorder = Recorder::default();
+            viewer.render(&tilemap, None, &mut recorder, &styles);
+            let origin = viewer.get_origin(tiles, 100.0);
+            let floor: Vec<i32> = [(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]
+                .iter()
+                .map(|(x, y)| viewer.project(origin, *x, *y, 0.0).x)
+                .collect();
+            (recorder.get_shadows(), floor)
+        };
+
+        let (shadows, floor) = render(-1.0);
+        let min_x = *floor.iter().min().unwrap();
+        let max_x = *floor.iter().max().unwrap();
+
+        assert!(!shadows.is_empty());
+        for point in shadows.iter().flatten() {
+            assert!(point.x >= min_x && point.x <= max_x);
+        }
+
+        assert!(render(1.0).0.is_empty());
+    }
+
+    #[test]
+    fn test_get_overlapped_tiles() {
+        let tiles = Size2d::new(4, 3);
+        let triangle = [(50.0, 50.0), (150.0, 50.0), (50.0, 120.0)];
+        let outside = [(-50.0, 10.0), (-10.0, 10.0), (-10.0, 90.0)];
+        let beyond = [(350.0, 250.0), (500.0, 250.0), (500.0, 400.0)];
+
+        assert_eq!(
+            get_overlapped_tiles(&triangle, tiles, 100.0),
+            Some((0..2, 0..2))
+        );
+        assert_eq!(get_overlapped_tiles(&outside, tiles, 100.0), None);
+        assert_eq!(
+            get_overlapped_tiles(&beyond, tiles, 100.0),
+            Some((3..4, 2..3))
+        );
+    }
+
+    #[test]
+    fn test_view_direction_of_three_four() {
+        assert_eq!(
+            Projection::three_four(0.5).get_view_direction(),
+            (0.0, 1.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn test_get_visible_sides() {
+        let mut viewer = AxonometricView::new(Projection::isometric(), 100, 50);
+        let mut get_sides = |orientation| {
+            viewer.set_orientation(orientation);
+            viewer.get_visible_sides()
+        };
+
+        assert_eq!(get_sides(Orientation::North), (Side::Front, Side::Right));
+        assert_eq!(get_sides(Orientation::East), (Side::Left, Side::Front));
+        assert_eq!(get_sides(Orientation::South), (Side::Back, Side::Left));
+        assert_eq!(get_sides(Orientation::West), (Side::Right, Side::Back));
+    }
+
+    #[test]
+    fn test_rotation_keeps_size() {
+        let mut viewer = AxonometricView::new(Projection::cabinet(), 100, 50);
+        let tiles = Size2d::new(2, 4);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        viewer.set_orientation(Orientation::South);
+
+        assert_eq!(viewer.get_size(tiles, &styles), Size2d::new(341, 191));
+
+        viewer.set_orientation(Orientation::East);
+
+        assert_eq!(viewer.get_size(tiles, &styles), Size2d::new(471, 121));
+    }
+
+    #[test]
+    fn test_label_margin_extends_size() {
+        let mut viewer = AxonometricView::new(Projection::cabinet(), 100, 50);
+        let tiles = Size2d::new(2, 4);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+        let size = viewer.get_size(tiles, &styles);
+
+        viewer.set_label_margin(20);
+
+        let with_margin = viewer.get_size(tiles, &styles);
+        assert!(with_margin.width() > size.width());
+        assert!(with_margin.height() > size.height());
+        assert_eq!(
+            viewer
+                .get_tile_viewport(tiles, Point2d::default(), tiles, &styles)
+                .get_size(),
+            size
+        );
+    }
+
+    #[test]
+    fn test_render_tall_solid_unclipped() {
+        let viewer = AxonometricView::new(Projection::three_four(1.0), 10, 20);
+        let tilemap = Tilemap2d::default(Size2d::new(1, 1), Tile::Solid(SolidId(0))).unwrap();
+        let solid = SolidStyle::with_height("tower", BoxStyle::shaded(RED), 50);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![solid], vec![], GridStyle::default())
+                .unwrap();
+        let mut recorder = Recorder::default();
+
+        viewer.render(&tilemap, None, &mut recorder, &styles);
+
+        let size = viewer.get_size(tilemap.get_size(), &styles);
+        let points = recorder.get_points(Selection::Tile(0));
+        assert_eq!(size, Size2d::new(10, 60));
+        assert_eq!(points.iter().map(|p| p.y).min(), Some(0));
+        assert_eq!(points.iter().map(|p| p.y).max(), Some(60));
+    }
+
+    #[test]
+    fn test_get_size_of_cabinet() {
+        let viewer = AxonometricView::new(Projection::cabinet(), 100, 50);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_size(Size2d::new(2, 4), &styles),
+            Size2d::new(341, 191)
+        );
+    }
+
+    #[test]
+    fn test_get_tile_viewport_of_military() {
+        let viewer = AxonometricView::new(Projection::military(), 100, 50);
+        let tiles = Size2d::new(2, 3);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_tile_viewport(tiles, Point2d::default(), tiles, &styles),
+            Viewport::new(Point2d::default(), viewer.get_size(tiles, &styles))
+        );
+        assert_eq!(
+            viewer.get_tile_viewport(tiles, Point2d::new(1, 0), Size2d::square(1), &styles),
+            Viewport::new(Point2d::new(212, 71), Size2d::new(142, 191))
+        );
+    }
+
+    #[test]
+    fn test_render_walls_on_near_edges() {
+        let viewer = AxonometricView::new(Projection::three_four(1.0), 10, 20);
+        let mut tilemap = Tilemap2d::default(Size2d::square(1), Tile::Empty).unwrap();
+        tilemap.set_border(0, Side::Front, Border::Wall(WallId(0)));
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(5, 5), None);
+        assert_eq!(pick(5, 25), Some(Selection::Border(0, Side::Front)));
+    }
+
+    #[test]
+    fn test_pick_cabinet() {
+        let viewer = AxonometricView::new(Projection::cabinet(), 100, 50);
+        let tilemap = Tilemap2d::new(
+            Size2d::new(2, 1),
+            vec![Tile::Solid(SolidId(0)), Tile::Floor(FloorId(0))],
+        )
+        .unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        // front face of the solid
+        assert_eq!(pick(50, 80), Some(Selection::Tile(0)));
+        // side face of the solid
+        assert_eq!(pick(120, 60), Some(Selection::Tile(0)));
+        // floor next to the solid
+        assert_eq!(pick(180, 80), Some(Selection::Tile(1)));
+        assert_eq!(pick(180, 20), None);
+    }
+
+    #[test]
+    fn test_render_upright_images() {
+        let tilemap = Tilemap2d::default(Size2d::new(1, 1), Tile::Solid(SolidId(0))).unwrap();
+        let aab = BoxStyle::shaded(RED)
+            .textured(Some(Image::new("top.png")), Some(Image::new("side.png")));
+        let styles = StyleMgr::without_manager(
+            vec![],
+            vec![],
+            vec![SolidStyle::new("solid", aab)],
+            vec![],
+            GridStyle::default(),
+        )
+        .unwrap();
+
+        for orientation in [Orientation::North, Orientation::South] {
+            let mut viewer = AxonometricView::new(Projection::isometric(), 10, 20);
+            viewer.set_orientation(orientation);
+            let mut recorder = Recorder::default();
+
+            viewer.render(&tilemap, None, &mut recorder, &styles);
+
+            let images = recorder.get_images();
+            let paths: Vec<&str> = images.iter().map(|i| i.0.as_str()).collect();
+            assert_eq!(paths, vec!["top.png", "side.png", "side.png"]);
+
+            for (_path, [p0, p1, p2, p3]) in &images[1..] {
+                assert_eq!(p0.x, p1.x);
+                assert!(p0.y < p1.y);
+                assert!(p0.x < p3.x);
+                assert_eq!(*p2, *p1 + (*p3 - *p0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_jittered_floors() {
+        let viewer = AxonometricView::new(Projection::isometric(), 10, 20);
+        let tilemap = Tilemap2d::default(Size2d::new(2, 1), Tile::Floor(FloorId(0))).unwrap();
+        let jitter = ColorJitter::new(5, 20);
+        let floor = FloorStyle::new("floor", Color::gray(100)).jittered(jitter);
+        let styles =
+            StyleMgr::without_manager(vec![floor], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+        let mut recorder = Recorder::default();
+
+        viewer.render(&tilemap, None, &mut recorder, &styles);
+        let colors = recorder.get_colors();
+
+        assert_eq!(
+            colors,
+            vec![
+                jitter.apply(Color::gray(100), 0, 0),
+                jitter.apply(Color::gray(100), 1, 0)
+            ]
+        );
+        assert_ne!(colors[0], colors[1]);
+    }
+
+    #[test]
+    fn test_render_octagon_prism() {
+        let viewer = AxonometricView::new(Projection::three_four(1.0), 10, 20);
+        let mut tilemap = Tilemap2d::default(Size2d::new(2, 1), Tile::Empty).unwrap();
+        tilemap.set_border(0, Side::Right, Border::Wall(WallId(0)));
+        let node = NodeStyle::with_shape(
+            "node",
+            BoxStyle::new(RED, GREEN, WHITE),
+            NodeShape::Octagon,
+            4,
+            None,
+        );
+        let wall = WallStyle::new("wall", BoxStyle::shaded(BLUE), NodeId(0), 2);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![node], vec![], vec![wall], GridStyle::default())
+                .unwrap();
+        let mut recorder = Recorder::default();
+
+        viewer.render(&tilemap, None, &mut recorder, &styles);
+        let colors = recorder.get_colors();
+
+        let blend = RED * 0.5 + GREEN * 0.5;
+        let node_colors = vec![RED, RED, blend, WHITE];
+
+        assert_eq!(colors[0..4], node_colors);
+        assert_eq!(colors[colors.len() - 4..], node_colors);
+    }
+
+    #[test]
+    fn test_render_hidden_face_from_south() {
+        let mut viewer = AxonometricView::new(Projection::three_four(1.0), 10, 20);
+        let tilemap = Tilemap2d::default(Size2d::square(1), Tile::Solid(SolidId(0))).unwrap();
+        let style = BoxStyle::with_hidden_faces(RED, GREEN, WHITE, BLUE, BLACK);
+        let solid = SolidStyle::new("solid", style);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![solid], vec![], GridStyle::default())
+                .unwrap();
+        let render = |viewer: &AxonometricView| {
+            let mut recorder = Recorder::default();
+            viewer.render(&tilemap, None, &mut recorder, &styles);
+            recorder.get_colors()
+        };
+
+        assert_eq!(render(&viewer), vec![WHITE, RED]);
+
+        viewer.set_orientation(Orientation::South);
+
+        assert_eq!(render(&viewer), vec![WHITE, BLUE]);
+
+        viewer.set_orientation(Orientation::East);
+
+        assert_eq!(render(&viewer), vec![WHITE, BLACK]);
+    }
+}
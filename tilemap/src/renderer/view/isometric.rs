@@ -1,380 +1,152 @@
-use crate::math::color::Color;
 use crate::math::point2d::Point2d;
-use crate::math::side::Side;
 use crate::math::size2d::Size2d;
 use crate::port::renderer::Renderer;
-use crate::renderer::border::{calculate_horizontal_border, calculate_vertical_border};
-use crate::renderer::node::{calculate_node_styles, Node};
-use crate::renderer::style::aab::BoxStyle;
+use crate::renderer::shadow::Sun;
 use crate::renderer::style::StyleMgr;
+use crate::renderer::view::axonometric::{AxonometricView, Cutaway, Orientation, Projection};
 use crate::renderer::view::View;
-use crate::tilemap::border::{get_vertical_borders_size, Border};
-use crate::tilemap::tile::Tile;
+use crate::renderer::viewport::Viewport;
 use crate::tilemap::tilemap2d::Tilemap2d;
+use crate::tilemap::visibility::VisibilityMask;
 
 /// Renders a [`Tilemap2d`](crate::tilemap::tilemap2d::Tilemap2d) with an [`isometric view`](https://en.wikipedia.org/wiki/Isometric_projection).
+///
+/// It is an [`AxonometricView`] with the slopes of pixel art, rounded to whole pixels per tile.
 pub struct IsometricView {
     delta: Point2d,
-    tile_size: u32,
-    tile_height: i32,
+    view: AxonometricView,
 }
 
 impl View for IsometricView {
-    fn get_size(&self, tiles: Size2d) -> Size2d {
-        self.calculate_floor_size(tiles) + Size2d::new(0, self.tile_height as u32)
+    fn get_size(&self, tiles: Size2d, styles: &StyleMgr) -> Size2d {
+        self.view.get_size(tiles, styles)
     }
 
-    fn render(&self, tilemap: &Tilemap2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
-        let tiles = tilemap.get_size();
-        let vertical_size = get_vertical_borders_size(tilemap.get_size());
-        let nodes =
-            calculate_node_styles(styles.get_node_styles(), styles.get_wall_styles(), tilemap);
-        let mut start = self.get_start(tiles);
-        let mut index = 0;
-
-        for row in 0..tiles.height() {
-            let mut point = start;
-
-            for _x in 0..tiles.width() {
-                let tile = tilemap.get_tile(index);
-
-                match tile {
-                    Tile::Empty => {}
-                    Tile::Floor(id) => self.render_tile(
-                        renderer,
-                        point,
-                        *styles.get_floor_style(id).get_floor_color(),
-                    ),
-                    Tile::Solid(id) => {
-                        let style = styles.get_solid_style(id);
-                        self.render_box(
-                            renderer,
-                            point,
-                            self.delta,
-                            self.delta,
-                            style.get_aab_style(),
-                            (true, true),
-                        )
-                    }
-                }
-
-                let node_index = index + row as usize;
-
-                match nodes[node_index] {
-                    Node::NoNode => {}
-                    Node::InnerNode => {}
-                    Node::OuterNode(style) => {
-                        let delta_half = Self::calculate_delta(style.get_half());
-                        let delta_size = Self::calculate_delta(style.get_size());
-                        let back = self.get_reverse_left_box(point, delta_half);
-                        let back = self.get_reverse_right_box(back, delta_half);
-
-                        self.render_box(
-                            renderer,
-                            back,
-                            delta_size,
-                            delta_size,
-                            style.get_style(),
-                            (true, true),
-                        );
-                    }
-                }
-
-                match tilemap.get_border(index, Side::Back) {
-                    Border::NoBorder => {}
-                    Border::Wall(id) => {
-                        let style = styles.get_wall_style(id);
-                        let thickness = style.get_thickness();
-                        let (start, length) =
-                            calculate_horizontal_border(&nodes, self.tile_size, index, row);
-                        let delta_half = Self::calculate_delta(thickness / 2);
-                        let delta_start = Self::calculate_delta(start as u32);
-                        let back = self.get_reverse_left_box(point, delta_half);
-                        let back = self.get_right_box(back, delta_start);
-
-                        self.render_box(
-                            renderer,
-                            back,
-                            Self::calculate_delta(length),
-                            Self::calculate_delta(thickness),
-                            style.get_aab_style(),
-                            (true, false),
-                        );
-                    }
-                }
-
-                match tilemap.get_border(index, Side::Left) {
-                    Border::NoBorder => {}
-                    Border::Wall(id) => {
-                        let style = styles.get_wall_style(id);
-                        let thickness = style.get_thickness();
-                        let border_index = index + row as usize;
-                        let (start, length) = calculate_vertical_border(
-                            &nodes,
-                            self.tile_size,
-                            vertical_size,
-                            border_index,
-                        );
-                        let delta_half = Self::calculate_delta(thickness / 2);
-                        let delta_start = Self::calculate_delta(start as u32);
-                        let left = self.get_reverse_right_box(point, delta_half);
-                        let left = self.get_left_box(left, delta_start);
-
-                        self.render_box(
-                            renderer,
-                            left,
-                            Self::calculate_delta(thickness),
-                            Self::calculate_delta(length),
-                            style.get_aab_style(),
-                            (false, true),
-                        );
-                    }
-                }
-
-                // Move the point of the next tile in this row
-                point = self.get_right(point);
-                index += 1;
-            }
-
-            // Move the start point of the next row
-            start = self.get_left(start);
-        }
-    }
-
-    fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, style: &StyleMgr) {
-        self.render_grid_rows(tiles, renderer, style);
-        self.render_grid_columns(tiles, renderer, style);
-    }
-}
-
-impl IsometricView {
-    pub fn new(tile_size: u32, tile_height: u32) -> Self {
-        IsometricView {
-            tile_size,
-            delta: Self::calculate_delta(tile_size),
-            tile_height: tile_height as i32,
-        }
-    }
-
-    pub fn calculate_delta(size: u32) -> Point2d {
-        let delta_y = Self::calculate_delta_y(size);
-        Point2d::new(delta_y * 2, delta_y)
-    }
-
-    pub fn calculate_delta_y(size: u32) -> i32 {
-        ((size as f32) / (5.0_f32).sqrt()).ceil() as i32
-    }
-
-    /// Calculates the size needed to render the floor of the tilemap.
-    pub fn calculate_floor_size(&self, tiles: Size2d) -> Size2d {
-        let dx = self.delta.x as u32;
-        let dy = self.delta.y as u32;
-        let left_to_center = dx * tiles.width();
-        let center_to_right = dx * tiles.height();
-        let center_to_bottom = dy * tiles.width();
-        let center_to_top = dy * tiles.height();
-        Size2d::new(
-            left_to_center + center_to_right,
-            center_to_bottom + center_to_top,
-        )
-    }
-
-    /// Render a tile relative to its back point.
-    fn render_tile(&self, renderer: &mut dyn Renderer, back: Point2d, color: Color) {
-        renderer.render_transformed_rectangle(
-            back,
-            self.get_left(back),
-            self.get_front(back),
-            self.get_right(back),
-            color,
-        )
-    }
-
-    /// Render an axis aligned box.
-    fn render_box(
+    fn get_tile_viewport(
         &self,
-        renderer: &mut dyn Renderer,
-        back: Point2d,
-        delta_row: Point2d,
-        delta_column: Point2d,
-        style: &BoxStyle,
-        filter: (bool, bool),
-    ) {
-        self.render_ceiling(
-            renderer,
-            back,
-            delta_row,
-            delta_column,
-            *style.get_top_color(),
-        );
-
-        if filter.0 {
-            self.render_front(
-                renderer,
-                back,
-                delta_row,
-                delta_column,
-                *style.get_front_color(),
-            );
-        }
-
-        if filter.1 {
-            self.render_side(
-                renderer,
-                back,
-                delta_row,
-                delta_column,
-                *style.get_side_color(),
-            );
-        }
+        tiles: Size2d,
+        start: Point2d,
+        size: Size2d,
+        styles: &StyleMgr,
+    ) -> Viewport {
+        self.view.get_tile_viewport(tiles, start, size, styles)
     }
 
-    /// Render the top of an axis aligned box.
-    fn render_ceiling(
+    fn render(
         &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
         renderer: &mut dyn Renderer,
-        back: Point2d,
-        delta_row: Point2d,
-        delta_column: Point2d,
-        color: Color,
+        styles: &StyleMgr,
     ) {
-        let top_back = self.get_top(back);
-        renderer.render_transformed_rectangle(
-            top_back,
-            self.get_left_box(top_back, delta_column),
-            self.get_front_box(top_back, delta_row, delta_column),
-            self.get_right_box(top_back, delta_row),
-            color,
-        )
+        self.view.render(tilemap, visibility, renderer, styles)
     }
 
-    /// Render the front of an axis aligned box.
-    fn render_front(
-        &self,
-        renderer: &mut dyn Renderer,
-        back: Point2d,
-        delta_row: Point2d,
-        delta_column: Point2d,
-        color: Color,
-    ) {
-        let left0 = self.get_left_box(back, delta_column);
-        let left1 = self.get_top(left0);
-        let front0 = self.get_front_box(back, delta_row, delta_column);
-        let front1 = self.get_top(front0);
-        renderer.render_transformed_rectangle(left1, left0, front0, front1, color)
-    }
-
-    /// Render the side of an axis aligned box.
-    fn render_side(
-        &self,
-        renderer: &mut dyn Renderer,
-        back: Point2d,
-        delta_row: Point2d,
-        delta_column: Point2d,
-        color: Color,
-    ) {
-        let right0 = self.get_right_box(back, delta_row);
-        let right1 = self.get_top(right0);
-        let front0 = self.get_front_box(back, delta_row, delta_column);
-        let front1 = self.get_top(front0);
-        renderer.render_transformed_rectangle(right0, right1, front1, front0, color)
+    fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
+        self.view.render_grid(tiles, renderer, styles)
     }
+}
 
-    /// Render the lines of the grid between columns.
-    fn render_grid_columns(&self, tiles: Size2d, renderer: &mut dyn Renderer, style: &StyleMgr) {
-        let start = self.get_start(tiles);
-        let mut start_column = self.get_right(start);
-        let diff_column = self.get_diff_column(tiles);
-
-        for _column in 0..(tiles.width() - 1) {
-            let end_column = start_column + diff_column;
-
-            renderer.render_line(start_column, end_column, *style.get_grid_color());
-
-            start_column = self.get_right(start_column);
+impl IsometricView {
+    pub fn new(tile_size: u32, tile_height: u32) -> Self {
+        let delta = Self::calculate_delta(tile_size);
+        IsometricView {
+            delta,
+            view: AxonometricView::new(
+                Self::calculate_projection(tile_size, delta),
+                tile_size,
+                tile_height,
+            ),
         }
     }
 
-    /// Render the lines of the grid between rows.
-    fn render_grid_rows(&self, tiles: Size2d, renderer: &mut dyn Renderer, style: &StyleMgr) {
-        let start = self.get_start(tiles);
-        let mut start_row = self.get_left(start);
-        let diff_row = self.get_diff_row(tiles);
-
-        for _row in 0..(tiles.height() - 1) {
-            let end_row = start_row + diff_row;
-
-            renderer.render_line(start_row, end_row, *style.get_grid_color());
-
-            start_row = self.get_left(start_row);
+    /// Creates a view, where the [`Sun`] casts the shadows of solids, walls & nodes onto the floor.
+    pub fn with_sun(tile_size: u32, tile_height: u32, sun: Sun) -> Self {
+        let delta = Self::calculate_delta(tile_size);
+        IsometricView {
+            delta,
+            view: AxonometricView::with_sun(
+                Self::calculate_projection(tile_size, delta),
+                tile_size,
+                tile_height,
+                sun,
+            ),
         }
     }
 
-    /// Calculate the back point of the 1.tile.
-    fn get_start(&self, tiles: Size2d) -> Point2d {
-        Point2d::new(self.delta.x * tiles.height() as i32, self.tile_height)
+    pub fn get_orientation(&self) -> Orientation {
+        self.view.get_orientation()
     }
 
-    /// Calculate the front corner of the floor tile from the back corner.
-    fn get_front(&self, point: Point2d) -> Point2d {
-        self.get_front_box(point, self.delta, self.delta)
+    /// Rotates the camera around the tilemap without changing it.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.view.set_orientation(orientation)
     }
 
-    /// Calculate the front corner of an axis aligned box from the back corner.
-    fn get_front_box(&self, point: Point2d, delta_row: Point2d, delta_column: Point2d) -> Point2d {
-        Point2d::new(
-            point.x + delta_row.x - delta_column.x,
-            point.y + delta_row.y + delta_column.y,
-        )
+    pub fn get_cutaway(&self) -> Option<Cutaway> {
+        self.view.get_cutaway()
     }
 
-    /// Calculate the left corner of the floor tile from the back corner.
-    fn get_left(&self, point: Point2d) -> Point2d {
-        self.get_left_box(point, self.delta)
+    /// Lowers the walls in front of floors or renders all walls at full height.
+    pub fn set_cutaway(&mut self, cutaway: Option<Cutaway>) {
+        self.view.set_cutaway(cutaway)
     }
 
-    /// Calculate the left corner of an axis aligned box from the back corner.
-    fn get_left_box(&self, point: Point2d, delta: Point2d) -> Point2d {
-        Point2d::new(point.x - delta.x, point.y + delta.y)
+    pub fn get_label_margin(&self) -> u32 {
+        self.view.get_label_margin()
     }
 
-    fn get_reverse_left_box(&self, point: Point2d, delta: Point2d) -> Point2d {
-        Point2d::new(point.x + delta.x, point.y - delta.y)
+    /// Reserves space in the image at the left & back edge of the tilemap for the labels of the grid.
+    pub fn set_label_margin(&mut self, margin: u32) {
+        self.view.set_label_margin(margin)
     }
 
-    /// Calculate the right corner of the floor tile from the back corner.
-    fn get_right(&self, point: Point2d) -> Point2d {
-        self.get_right_box(point, self.delta)
-    }
-
-    /// Calculate the right corner of an axis aligned box from the back corner.
-    fn get_right_box(&self, point: Point2d, delta: Point2d) -> Point2d {
-        Point2d::new(point.x + delta.x, point.y + delta.y)
-    }
-
-    fn get_reverse_right_box(&self, point: Point2d, delta: Point2d) -> Point2d {
-        Point2d::new(point.x - delta.x, point.y - delta.y)
+    pub fn calculate_delta(size: u32) -> Point2d {
+        let delta_y = Self::calculate_delta_y(size);
+        Point2d::new(delta_y * 2, delta_y)
     }
 
-    /// Calculate the equivalent point on the ceiling from any point on the floor.
-    fn get_top(&self, point: Point2d) -> Point2d {
-        Point2d::new(point.x, point.y - self.tile_height)
+    pub fn calculate_delta_y(size: u32) -> i32 {
+        ((size as f32) / (5.0_f32).sqrt()).ceil() as i32
     }
 
-    /// Calculate the difference between the start & end point of a column.
-    fn get_diff_column(&self, tiles: Size2d) -> Point2d {
-        self.get_left(Point2d::default()) * tiles.height()
+    /// Calculates the size needed to render the floor of the tilemap.
+    pub fn calculate_floor_size(&self, tiles: Size2d) -> Size2d {
+        let dx = self.delta.x as u32;
+        let dy = self.delta.y as u32;
+        let left_to_center = dx * tiles.width();
+        let center_to_right = dx * tiles.height();
+        let center_to_bottom = dy * tiles.width();
+        let center_to_top = dy * tiles.height();
+        Size2d::new(
+            left_to_center + center_to_right,
+            center_to_bottom + center_to_top,
+        )
     }
 
-    /// Calculate the difference between the start & end point of a row.
-    fn get_diff_row(&self, tiles: Size2d) -> Point2d {
-        self.get_right(Point2d::default()) * tiles.width()
+    /// Calculates the projection, that moves by the rounded delta per tile.
+    fn calculate_projection(tile_size: u32, delta: Point2d) -> Projection {
+        let dx = delta.x as f32 / tile_size as f32;
+        let dy = delta.y as f32 / tile_size as f32;
+        Projection::new((dx, dy), (-dx, dy), (0.0, -1.0))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::color::{BLACK, PINK};
+    use crate::math::side::Side;
+    use crate::renderer::recorder::Recorder;
+    use crate::renderer::style::aab::BoxStyle;
+    use crate::renderer::style::grid::GridStyle;
+    use crate::renderer::style::wall::WallStyle;
+    use crate::tilemap::border::{Border, WallId};
+    use crate::tilemap::selection::Selection;
+    use crate::tilemap::tile::FloorId;
+    use crate::tilemap::tile::SolidId;
+    use crate::tilemap::tile::Tile;
+    use crate::tilemap::NodeId;
 
     #[test]
     fn test_calculate_delta_y() {
@@ -400,7 +172,104 @@ mod tests {
     #[test]
     fn test_get_size() {
         let viewer = IsometricView::new(100, 200);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
 
-        assert_eq!(viewer.get_size(Size2d::new(2, 3)), Size2d::new(450, 425));
+        assert_eq!(
+            viewer.get_size(Size2d::new(2, 3), &styles),
+            Size2d::new(450, 425)
+        );
+    }
+
+    #[test]
+    fn test_get_tile_viewport_of_whole_map() {
+        let viewer = IsometricView::new(100, 200);
+        let tiles = Size2d::new(2, 3);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_tile_viewport(tiles, Point2d::default(), tiles, &styles),
+            Viewport::new(Point2d::default(), viewer.get_size(tiles, &styles))
+        );
+    }
+
+    #[test]
+    fn test_get_tile_viewport_of_one_tile() {
+        let viewer = IsometricView::new(100, 200);
+        let tiles = Size2d::new(2, 3);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_tile_viewport(tiles, Point2d::new(1, 0), Size2d::square(1), &styles),
+            Viewport::new(Point2d::new(270, 45), Size2d::new(180, 290))
+        );
+    }
+
+    #[test]
+    fn test_pick_solid_occludes_floor() {
+        let viewer = IsometricView::new(100, 200);
+        let tilemap = Tilemap2d::new(
+            Size2d::new(1, 2),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(220, 245), Some(Selection::Tile(0)));
+        assert_eq!(pick(150, 245), Some(Selection::Tile(1)));
+        assert_eq!(pick(90, 50), Some(Selection::Tile(1)));
+        assert_eq!(pick(200, 50), None);
+    }
+
+    #[test]
+    fn test_pick_ignores_shadows() {
+        let viewer = IsometricView::with_sun(100, 200, Sun::new(0.0, -0.5, BLACK));
+        let tilemap = Tilemap2d::new(
+            Size2d::new(1, 2),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(220, 245), Some(Selection::Tile(0)));
+        assert_eq!(pick(150, 245), Some(Selection::Tile(1)));
+    }
+
+    #[test]
+    fn test_cutaway_keeps_back_wall() {
+        let mut viewer = IsometricView::new(100, 200);
+        let mut tilemap = Tilemap2d::default(Size2d::new(1, 1), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(0, Side::Back, Border::Wall(WallId(0)));
+        tilemap.set_border(0, Side::Front, Border::Wall(WallId(0)));
+        let wall = WallStyle::new("wall", BoxStyle::shaded(PINK), NodeId(0), 10);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![wall], GridStyle::default())
+                .unwrap();
+        let get_top = |viewer: &IsometricView, side| {
+            let mut recorder = Recorder::default();
+            viewer.render(&tilemap, None, &mut recorder, &styles);
+            let points = recorder.get_points(Selection::Border(0, side));
+            points.iter().map(|p| p.y).min().unwrap()
+        };
+        let back = get_top(&viewer, Side::Back);
+        let front = get_top(&viewer, Side::Front);
+
+        viewer.set_cutaway(Some(Cutaway::new(20, false)));
+
+        assert_eq!(get_top(&viewer, Side::Back), back);
+        assert!(get_top(&viewer, Side::Front) > front);
     }
 }
@@ -1,20 +1,258 @@
+use crate::math::point2d::Point2d;
 use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
 use crate::port::renderer::Renderer;
+use crate::port::style::{StyleKind, StyleRef};
+use crate::renderer::light::LightMap;
+use crate::renderer::picker::Picker;
+use crate::renderer::style::aab::BoxStyle;
+use crate::renderer::style::grid::{GridPosition, GridStyle};
 use crate::renderer::style::StyleMgr;
+use crate::renderer::viewport::{Viewport, ViewportRenderer};
+use crate::tilemap::selection::Selection;
 use crate::tilemap::tilemap2d::Tilemap2d;
+use crate::tilemap::visibility::VisibilityMask;
+use crate::utils::resource::Resource;
 
+pub mod axonometric;
 pub mod isometric;
 pub mod three_four;
 pub mod top_down;
 
+/// The distance of the grid labels to the edge of the tilemap in tiles, if the view has no label margin.
+const LABEL_OFFSET: f32 = 0.2;
+
+/// Returns the distance of the grid labels to the left & back edge of the tilemap in tiles.
+/// The labels are centered in the label margin or inside the tiles at the edge without one.
+fn get_label_offset(margin: u32, tile_size: Size2d) -> (f32, f32) {
+    if margin == 0 {
+        return (LABEL_OFFSET, LABEL_OFFSET);
+    }
+
+    let half = margin as f32 / 2.0;
+    (
+        -half / tile_size.width() as f32,
+        -half / tile_size.height() as f32,
+    )
+}
+
+/// Renders the lines, frame & labels of the grid.
+/// The projection maps a point on the ground, measured in tiles, to the rendered image.
+fn render_grid_style(
+    tiles: Size2d,
+    renderer: &mut dyn Renderer,
+    style: &GridStyle,
+    label_size: u32,
+    label_offset: (f32, f32),
+    project: impl Fn(f32, f32) -> Point2d,
+) {
+    let width = tiles.width() as f32;
+    let height = tiles.height() as f32;
+
+    renderer.set_layer(Layer::Grid);
+    renderer.set_style(StyleRef::new(StyleKind::Grid, style.get_name()));
+
+    for row in 1..tiles.height() {
+        let y = row as f32;
+        renderer.render_styled_line(project(0.0, y), project(width, y), style.get_line_style());
+    }
+
+    for column in 1..tiles.width() {
+        let x = column as f32;
+        renderer.render_styled_line(project(x, 0.0), project(x, height), style.get_line_style());
+    }
+
+    if let Some(frame) = style.get_frame_style() {
+        let corners = [
+            project(0.0, 0.0),
+            project(0.0, height),
+            project(width, height),
+            project(width, 0.0),
+        ];
+
+        for (index, corner) in corners.iter().enumerate() {
+            renderer.render_styled_line(*corner, corners[(index + 1) % corners.len()], frame);
+        }
+    }
+
+    if let Some(format) = style.get_label_format() {
+        let color = style.get_line_style().get_color();
+
+        for column in 0..tiles.width() {
+            let label = format.get_column_label(column);
+            let center = project(column as f32 + 0.5, label_offset.1);
+            renderer.render_text(center, &label, label_size, color);
+        }
+
+        for row in 0..tiles.height() {
+            let label = format.get_row_label(row);
+            let center = project(label_offset.0, row as f32 + 0.5);
+            renderer.render_text(center, &label, label_size, color);
+        }
+    }
+}
+
+/// Renders the outline of a face, if the [`BoxStyle`] has one.
+fn render_face_outline(renderer: &mut dyn Renderer, points: &[Point2d], style: &BoxStyle) {
+    if let Some(outline) = style.get_outline() {
+        renderer.render_outline(points, outline);
+    }
+}
+
+/// Renders the grid, if its [`style`](GridStyle) places it at this position.
+fn render_grid_at<V: View + ?Sized>(
+    view: &V,
+    position: GridPosition,
+    tiles: Size2d,
+    renderer: &mut dyn Renderer,
+    styles: &StyleMgr,
+) {
+    if styles.get_grid_style().get_position() == position {
+        view.render_grid(tiles, renderer, styles);
+    }
+}
+
+/// Returns the tilemap without unseen elements & the light map dimmed by the [`visibility mask`](VisibilityMask).
+/// The light is calculated before hiding, so hidden walls & solids still block it.
+/// Returns none for a mask, whose size doesn't match the tilemap, so nothing unseen is revealed.
+fn apply_visibility(
+    tilemap: &Tilemap2d,
+    visibility: Option<&VisibilityMask>,
+) -> Option<(Option<Tilemap2d>, LightMap)> {
+    let mut lights = LightMap::new(tilemap);
+
+    match visibility {
+        None => Some((None, lights)),
+        Some(mask) => {
+            let hidden = mask.hide_unseen(tilemap).ok()?;
+            lights.apply_visibility(mask);
+            Some((Some(hidden), lights))
+        }
+    }
+}
+
 pub trait View {
-    /// Returns the required size to fully render the tilemap.
-    fn get_size(&self, tiles: Size2d) -> Size2d;
+    /// Returns the required size to fully render the tilemap including the label margin.
+    /// The 3d views reserve the height of the tallest style.
+    fn get_size(&self, tiles: Size2d, styles: &StyleMgr) -> Size2d;
 
     /// Renders a [`tilemap`](crate::tilemap::tilemap2d::Tilemap2d) with a specific [`renderer`](crate::port::renderer::Renderer)
     /// & [`style`](crate::renderer::style::Style).
-    fn render(&self, tilemap: &Tilemap2d, renderer: &mut dyn Renderer, styles: &StyleMgr);
+    ///
+    /// The optional [`visibility mask`](VisibilityMask) hides unseen tiles & dims the ones seen before.
+    /// Nothing is rendered, if its size doesn't match the tilemap.
+    fn render(
+        &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+    );
 
-    /// Renders the grid for the tiles.
+    /// Returns the [`viewport`](Viewport) showing a rectangle of tiles without zoom.
+    fn get_tile_viewport(
+        &self,
+        tiles: Size2d,
+        start: Point2d,
+        size: Size2d,
+        styles: &StyleMgr,
+    ) -> Viewport;
+
+    /// Renders only the part of the tilemap inside the [`viewport`](Viewport).
+    /// The output starts at the origin & has the size of the viewport.
+    fn render_viewport(
+        &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
+        viewport: &Viewport,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+    ) {
+        let mut viewport_renderer = ViewportRenderer::new(*viewport, renderer);
+        self.render(tilemap, visibility, &mut viewport_renderer, styles);
+    }
+
+    /// Renders the grid for the tiles with the [`grid style`](GridStyle) of the styles.
     fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, styles: &StyleMgr);
+
+    /// Returns the visible tile, border or node at a point of the rendered image.
+    /// Unseen elements can't be picked.
+    fn pick(
+        &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
+        point: Point2d,
+        styles: &StyleMgr,
+    ) -> Option<Selection> {
+        let mut picker = Picker::new(point);
+        self.render(tilemap, visibility, &mut picker, styles);
+        picker.get_selection()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::BLACK;
+    use crate::renderer::recorder::Recorder;
+    use crate::renderer::style::grid::{LabelFormat, LineStyle};
+    use crate::renderer::view::top_down::TopDownView;
+    use crate::tilemap::tile::{FloorId, Tile};
+    use crate::tilemap::visibility::Visibility;
+
+    #[test]
+    fn test_render_grid_with_frame_and_labels() {
+        let style = GridStyle::new(
+            "grid",
+            LineStyle::solid(BLACK, 1),
+            Some(LineStyle::solid(BLACK, 3)),
+            Some(LabelFormat::Chess),
+            GridPosition::AboveWalls,
+        );
+        let mut recorder = Recorder::default();
+        let project = |x: f32, y: f32| Point2d::new((x * 10.0) as i32, (y * 10.0) as i32);
+        let offset = get_label_offset(0, Size2d::square(10));
+
+        render_grid_style(Size2d::new(2, 1), &mut recorder, &style, 4, offset, project);
+
+        assert_eq!(
+            recorder.get_styled_lines(),
+            vec![
+                (Point2d::new(10, 0), Point2d::new(10, 10), 1),
+                (Point2d::new(0, 0), Point2d::new(0, 10), 3),
+                (Point2d::new(0, 10), Point2d::new(20, 10), 3),
+                (Point2d::new(20, 10), Point2d::new(20, 0), 3),
+                (Point2d::new(20, 0), Point2d::new(0, 0), 3),
+            ]
+        );
+        assert_eq!(
+            recorder.get_texts(),
+            vec![
+                (Point2d::new(5, 2), "A".to_string()),
+                (Point2d::new(15, 2), "B".to_string()),
+                (Point2d::new(2, 5), "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_nothing_with_visibility_mask_of_wrong_size() {
+        let viewer = TopDownView::new(Size2d::square(10));
+        let tilemap = Tilemap2d::default(Size2d::new(2, 1), Tile::Floor(FloorId(0))).unwrap();
+        let mask = VisibilityMask::default(Size2d::new(1, 2), Visibility::Seen).unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+        let mut recorder = Recorder::default();
+
+        assert!(apply_visibility(&tilemap, Some(&mask)).is_none());
+
+        viewer.render(&tilemap, Some(&mask), &mut recorder, &styles);
+
+        assert!(recorder.calls.is_empty());
+        assert_eq!(
+            viewer.pick(&tilemap, Some(&mask), Point2d::new(5, 5), &styles),
+            None
+        );
+    }
 }
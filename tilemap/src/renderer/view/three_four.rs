@@ -1,202 +1,291 @@
-use crate::math::color::Color;
 use crate::math::point2d::Point2d;
-use crate::math::side::Side;
 use crate::math::size2d::Size2d;
 use crate::port::renderer::Renderer;
-use crate::renderer::border::{calculate_horizontal_border, calculate_vertical_border};
-use crate::renderer::node::{calculate_node_styles, Node};
-use crate::renderer::style::aab::BoxStyle;
 use crate::renderer::style::StyleMgr;
+use crate::renderer::view::axonometric::{AxonometricView, Cutaway, Orientation, Projection};
 use crate::renderer::view::View;
-use crate::tilemap::border::{get_vertical_borders_size, Border};
-use crate::tilemap::tile::Tile;
+use crate::renderer::viewport::Viewport;
 use crate::tilemap::tilemap2d::Tilemap2d;
+use crate::tilemap::visibility::VisibilityMask;
 
 /// Renders a [`Tilemap2d`](crate::tilemap::tilemap2d::Tilemap2d) with a 3/4 view.
+///
+/// It is an [`AxonometricView`], that shows the top & front faces.
+/// The width of a tile is its size in the tilemap & its height is the visible depth.
 pub struct ThreeFourView {
-    tile_size: Size2d,
-    tile_height: u32,
+    view: AxonometricView,
 }
 
 impl View for ThreeFourView {
-    fn get_size(&self, tiles: Size2d) -> Size2d {
-        tiles * self.tile_size + Size2d::new(0, self.tile_height)
-    }
-
-    fn render(&self, tilemap: &Tilemap2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
-        let nodes =
-            calculate_node_styles(styles.get_node_styles(), styles.get_wall_styles(), tilemap);
-        let tiles = tilemap.get_size();
-        let vertical_size = get_vertical_borders_size(tilemap.get_size());
-        let mut y = 0;
-        let mut index = 0;
-
-        for row in 0..tiles.height() {
-            let mut x = 0;
-
-            for _x in 0..tiles.width() {
-                let tile = tilemap.get_tile(index);
-
-                match tile {
-                    Tile::Empty => {}
-                    Tile::Floor(id) => self.render_tile(
-                        renderer,
-                        x,
-                        y + self.tile_height as i32,
-                        *styles.get_floor_style(id).get_floor_color(),
-                    ),
-                    Tile::Solid(id) => {
-                        self.render_aabb(
-                            renderer,
-                            x,
-                            y,
-                            self.tile_size.width(),
-                            self.tile_size.height(),
-                            styles.get_solid_style(id).get_aab_style(),
-                        );
-                    }
-                }
-
-                let node_index = index + row as usize;
-
-                match nodes[node_index] {
-                    Node::NoNode => {}
-                    Node::InnerNode => {}
-                    Node::OuterNode(style) => {
-                        let half = style.get_half() as i32;
-
-                        self.render_aabb(
-                            renderer,
-                            x - half,
-                            y - half,
-                            style.get_size(),
-                            style.get_size(),
-                            style.get_style(),
-                        );
-                    }
-                }
-
-                match tilemap.get_border(index, Side::Back) {
-                    Border::NoBorder => {}
-                    Border::Wall(id) => {
-                        let style = styles.get_wall_style(id);
-                        let thickness = style.get_thickness();
-                        let (start, length) =
-                            calculate_horizontal_border(&nodes, self.tile_size.width(), index, row);
-
-                        self.render_aabb(
-                            renderer,
-                            x + start,
-                            y - thickness as i32 / 2,
-                            length,
-                            thickness,
-                            style.get_aab_style(),
-                        );
-                    }
-                }
-
-                match tilemap.get_border(index, Side::Left) {
-                    Border::NoBorder => {}
-                    Border::Wall(id) => {
-                        let style = styles.get_wall_style(id);
-                        let thickness = style.get_thickness();
-                        let (start, length) = calculate_vertical_border(
-                            &nodes,
-                            self.tile_size.width(),
-                            vertical_size,
-                            index,
-                        );
-
-                        self.render_aabb(
-                            renderer,
-                            x - thickness as i32 / 2,
-                            y + start,
-                            thickness,
-                            length,
-                            style.get_aab_style(),
-                        );
-                    }
-                }
-
-                x += self.tile_size.width() as i32;
-                index += 1;
-            }
-
-            y += self.tile_size.height() as i32;
-        }
+    fn get_size(&self, tiles: Size2d, styles: &StyleMgr) -> Size2d {
+        self.view.get_size(tiles, styles)
     }
 
-    fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
-        let size = self.get_size(tiles);
-        let mut y = self.tile_height + self.tile_size.height();
-
-        for _row in 0..(tiles.height() - 1) {
-            renderer.render_line(
-                Point2d::new(0, y as i32),
-                Point2d::new(size.width() as i32, y as i32),
-                *styles.get_grid_color(),
-            );
-
-            y += self.tile_size.height();
-        }
+    fn get_tile_viewport(
+        &self,
+        tiles: Size2d,
+        start: Point2d,
+        size: Size2d,
+        styles: &StyleMgr,
+    ) -> Viewport {
+        self.view.get_tile_viewport(tiles, start, size, styles)
+    }
 
-        y = self.tile_height;
+    fn render(
+        &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+    ) {
+        self.view.render(tilemap, visibility, renderer, styles)
+    }
 
-        for column in 0..(tiles.width() - 1) {
-            let x = ((column + 1) * self.tile_size.width()) as i32;
-            renderer.render_line(
-                Point2d::new(x, y as i32),
-                Point2d::new(x, size.height() as i32),
-                *styles.get_grid_color(),
-            );
-        }
+    fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
+        self.view.render_grid(tiles, renderer, styles)
     }
 }
 
 impl ThreeFourView {
     pub fn new(tile_size: Size2d, tile_height: u32) -> Self {
+        let depth = tile_size.height() as f32 / tile_size.width() as f32;
+
         ThreeFourView {
-            tile_size,
-            tile_height,
+            view: AxonometricView::new(
+                Projection::three_four(depth),
+                tile_size.width(),
+                tile_height,
+            ),
         }
     }
 
-    fn render_aabb(
-        &self,
-        renderer: &mut dyn Renderer,
-        x: i32,
-        y: i32,
-        size_x: u32,
-        size_y: u32,
-        style: &BoxStyle,
-    ) {
-        // render top
+    pub fn get_orientation(&self) -> Orientation {
+        self.view.get_orientation()
+    }
 
-        renderer.render_rectangle(x, y, Size2d::new(size_x, size_y), *style.get_top_color());
+    /// Rotates the camera around the tilemap without changing it.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.view.set_orientation(orientation)
+    }
 
-        // render front
+    pub fn get_cutaway(&self) -> Option<Cutaway> {
+        self.view.get_cutaway()
+    }
 
-        renderer.render_rectangle(
-            x,
-            y + size_y as i32,
-            Size2d::new(size_x, self.tile_height),
-            *style.get_front_color(),
-        );
+    /// Lowers the walls in front of floors or renders all walls at full height.
+    pub fn set_cutaway(&mut self, cutaway: Option<Cutaway>) {
+        self.view.set_cutaway(cutaway)
+    }
+
+    pub fn get_label_margin(&self) -> u32 {
+        self.view.get_label_margin()
     }
 
-    fn render_tile(&self, renderer: &mut dyn Renderer, x: i32, y: i32, color: Color) {
-        renderer.render_rectangle(x, y, self.tile_size, color)
+    /// Reserves space in the image at the left & back edge of the tilemap for the labels of the grid.
+    pub fn set_label_margin(&mut self, margin: u32) {
+        self.view.set_label_margin(margin)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::color::PINK;
+    use crate::math::side::Side;
+    use crate::renderer::style::aab::BoxStyle;
+    use crate::renderer::style::grid::GridStyle;
+    use crate::renderer::style::solid::SolidStyle;
+    use crate::renderer::style::wall::{WallJoin, WallStyle};
+    use crate::tilemap::border::Border;
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::selection::Selection;
+    use crate::tilemap::tile::FloorId;
+    use crate::tilemap::tile::SolidId;
+    use crate::tilemap::tile::Tile;
+    use crate::tilemap::NodeId;
 
     #[test]
     fn test_get_size() {
         let viewer = ThreeFourView::new(Size2d::new(15, 25), 35);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_size(Size2d::new(2, 3), &styles),
+            Size2d::new(30, 110)
+        );
+    }
+
+    #[test]
+    fn test_get_tile_viewport() {
+        let viewer = ThreeFourView::new(Size2d::new(15, 25), 35);
+        let tiles = Size2d::new(5, 6);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_tile_viewport(tiles, Point2d::new(1, 2), Size2d::new(3, 4), &styles),
+            Viewport::new(Point2d::new(15, 50), Size2d::new(45, 135))
+        );
+    }
+
+    #[test]
+    fn test_pick_solid_occludes_floor() {
+        let viewer = ThreeFourView::new(Size2d::square(10), 20);
+        let tilemap = Tilemap2d::new(
+            Size2d::new(1, 2),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(5, 5), None);
+        assert_eq!(pick(5, 15), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 25), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 35), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 45), None);
+    }
+
+    #[test]
+    fn test_pick_low_solid() {
+        let viewer = ThreeFourView::new(Size2d::square(10), 20);
+        let tilemap = Tilemap2d::new(
+            Size2d::new(1, 2),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let solid = SolidStyle::with_height("rock", BoxStyle::shaded(PINK), 5);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![solid], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(5, 15), None);
+        assert_eq!(pick(5, 22), Some(Selection::Tile(0)));
+        assert_eq!(pick(5, 30), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 37), Some(Selection::Tile(1)));
+    }
+
+    #[test]
+    fn test_pick_tall_solid() {
+        let viewer = ThreeFourView::new(Size2d::square(10), 20);
+        let tilemap = Tilemap2d::new(
+            Size2d::new(1, 2),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let solid = SolidStyle::with_height("tower", BoxStyle::shaded(PINK), 30);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![solid], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(
+            viewer.get_size(Size2d::new(1, 2), &styles),
+            Size2d::new(10, 50)
+        );
+        assert_eq!(pick(5, 5), None);
+        assert_eq!(pick(5, 15), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 45), Some(Selection::Tile(1)));
+    }
+
+    #[test]
+    fn test_pick_from_south() {
+        let mut viewer = ThreeFourView::new(Size2d::square(10), 20);
+        viewer.set_orientation(Orientation::South);
+        let tilemap = Tilemap2d::new(
+            Size2d::new(1, 2),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(
+            viewer.get_size(Size2d::new(1, 2), &styles),
+            Size2d::new(10, 40)
+        );
+        assert_eq!(pick(5, 5), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 15), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 25), Some(Selection::Tile(1)));
+        assert_eq!(pick(5, 35), Some(Selection::Tile(0)));
+    }
+
+    #[test]
+    fn test_pick_with_cutaway() {
+        let mut viewer = ThreeFourView::new(Size2d::square(10), 20);
+        let mut tilemap = Tilemap2d::default(Size2d::new(1, 2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(1, Side::Back, Border::Wall(WallId(0)));
+        let wall = WallStyle::new("wall", BoxStyle::shaded(PINK), NodeId(0), 2);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![wall], GridStyle::default())
+                .unwrap();
+        let wall = Some(Selection::Border(1, Side::Back));
+
+        let pick =
+            |viewer: &ThreeFourView, y| viewer.pick(&tilemap, None, Point2d::new(5, y), &styles);
+
+        assert_eq!(pick(&viewer, 25), wall);
+
+        viewer.set_cutaway(Some(Cutaway::new(5, false)));
+
+        assert_eq!(pick(&viewer, 22), Some(Selection::Tile(0)));
+        assert_eq!(pick(&viewer, 25), wall);
+
+        viewer.set_cutaway(Some(Cutaway::footprint(false)));
+
+        assert_eq!(pick(&viewer, 25), Some(Selection::Tile(0)));
+        assert_eq!(pick(&viewer, 30), wall);
+    }
+
+    #[test]
+    fn test_pick_low_wall() {
+        let viewer = ThreeFourView::new(Size2d::square(10), 20);
+        let mut tilemap = Tilemap2d::default(Size2d::new(1, 2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(1, Side::Back, Border::Wall(WallId(0)));
+        let wall = WallStyle::with_height("hedge", BoxStyle::shaded(PINK), NodeId(0), 2, 5);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![wall], GridStyle::default())
+                .unwrap();
+
+        let pick = |y| viewer.pick(&tilemap, None, Point2d::new(5, y), &styles);
+
+        assert_eq!(pick(22), Some(Selection::Tile(0)));
+        assert_eq!(pick(25), Some(Selection::Border(1, Side::Back)));
+    }
+
+    #[test]
+    fn test_pick_mitered_corner() {
+        let viewer = ThreeFourView::new(Size2d::square(10), 20);
+        let mut tilemap = Tilemap2d::default(Size2d::square(2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(3, Side::Back, Border::Wall(WallId(0)));
+        tilemap.set_border(3, Side::Left, Border::Wall(WallId(1)));
+        let walls = ["back", "left"]
+            .iter()
+            .map(|name| {
+                WallStyle::new(*name, BoxStyle::shaded(PINK), NodeId(0), 4)
+                    .joined(WallJoin::Mitered)
+            })
+            .collect();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], walls, GridStyle::default()).unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
 
-        assert_eq!(viewer.get_size(Size2d::new(2, 3)), Size2d::new(30, 110));
+        assert_eq!(pick(9, 10), Some(Selection::Node(4)));
+        assert_eq!(pick(11, 9), Some(Selection::Node(4)));
+        assert_eq!(pick(13, 9), Some(Selection::Border(3, Side::Back)));
+        assert_eq!(pick(9, 13), Some(Selection::Border(3, Side::Left)));
     }
 }
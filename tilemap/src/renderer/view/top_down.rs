@@ -1,88 +1,172 @@
 use crate::math::color::Color;
 use crate::math::point2d::Point2d;
 use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
 use crate::port::renderer::Renderer;
+use crate::port::style::{Face, StyleKind, StyleRef};
 use crate::renderer::border::{calculate_horizontal_border, calculate_vertical_border};
+use crate::renderer::light::LightMap;
 use crate::renderer::node::{calculate_node_styles, Node};
+use crate::renderer::style::aab::BoxStyle;
+use crate::renderer::style::floor::FloorStyle;
+use crate::renderer::style::grid::GridPosition;
+use crate::renderer::style::node::NodeShape;
 use crate::renderer::style::StyleMgr;
-use crate::renderer::view::View;
-use crate::tilemap::border::{get_horizontal_borders_size, get_vertical_borders_size, Border};
+use crate::renderer::view::{
+    apply_visibility, get_label_offset, render_face_outline, render_grid_at, render_grid_style,
+    View,
+};
+use crate::renderer::viewport::Viewport;
+use crate::tilemap::border::{
+    get_horizontal_borders_size, get_tile_of_horizontal_border, get_tile_of_vertical_border,
+    get_vertical_borders_size, Border,
+};
 use crate::tilemap::node::get_nodes_size;
+use crate::tilemap::selection::Selection;
 use crate::tilemap::tile::Tile;
 use crate::tilemap::tilemap2d::Tilemap2d;
+use crate::tilemap::visibility::VisibilityMask;
+use crate::utils::resource::Resource;
 
 /// Renders a [`Tilemap2d`](crate::tilemap::tilemap2d::Tilemap2d) with a top-down view.
 pub struct TopDownView {
     tile_size: Size2d,
+    /// The space reserved in the image for the labels of the grid at the left & back edge.
+    label_margin: u32,
 }
 
 impl View for TopDownView {
-    fn get_size(&self, tiles: Size2d) -> Size2d {
-        tiles * self.tile_size
+    fn get_size(&self, tiles: Size2d, _styles: &StyleMgr) -> Size2d {
+        let margin = Size2d::square(self.label_margin);
+        tiles * self.tile_size + margin
     }
 
-    fn render(&self, tilemap: &Tilemap2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
-        self.render_tiles(tilemap, renderer, styles);
+    fn get_tile_viewport(
+        &self,
+        _tiles: Size2d,
+        start: Point2d,
+        size: Size2d,
+        _styles: &StyleMgr,
+    ) -> Viewport {
+        Viewport::new(
+            self.get_start() + start * self.tile_size,
+            size * self.tile_size,
+        )
+    }
+
+    fn render(
+        &self,
+        tilemap: &Tilemap2d,
+        visibility: Option<&VisibilityMask>,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+    ) {
+        let Some((hidden, lights)) = apply_visibility(tilemap, visibility) else {
+            return;
+        };
+        let tilemap = hidden.as_ref().unwrap_or(tilemap);
+
+        self.render_tiles(tilemap, &lights, renderer, styles, Layer::Floors);
+        self.render_tiles(tilemap, &lights, renderer, styles, Layer::Solids);
+
+        let tiles = tilemap.get_size();
+        render_grid_at(self, GridPosition::BelowWalls, tiles, renderer, styles);
 
-        let nodes =
-            calculate_node_styles(styles.get_node_styles(), styles.get_wall_styles(), tilemap);
+        let nodes = calculate_node_styles(styles, tilemap);
 
-        self.render_horizontal_borders(tilemap, &nodes, renderer, styles);
-        self.render_vertical_borders(tilemap, &nodes, renderer, styles);
-        self.render_nodes(tilemap, &nodes, renderer);
+        self.render_horizontal_borders(tilemap, &lights, &nodes, renderer, styles);
+        self.render_vertical_borders(tilemap, &lights, &nodes, renderer, styles);
+        self.render_nodes(tilemap, &lights, &nodes, renderer);
+
+        render_grid_at(self, GridPosition::AboveWalls, tiles, renderer, styles);
     }
 
     fn render_grid(&self, tiles: Size2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
-        let size = self.get_size(tiles);
-
-        for row in 0..(tiles.height() - 1) {
-            let y = ((row + 1) * self.tile_size.height()) as i32;
-            renderer.render_line(
-                Point2d::new(0, y),
-                Point2d::new(size.width() as i32, y),
-                *styles.get_grid_color(),
-            );
-        }
-
-        for column in 0..(tiles.width() - 1) {
-            let x = ((column + 1) * self.tile_size.width()) as i32;
-            renderer.render_line(
-                Point2d::new(x, 0),
-                Point2d::new(x, size.height() as i32),
-                *styles.get_grid_color(),
-            );
-        }
+        let tile_size = self.tile_size;
+        let label_size = tile_size.width().min(tile_size.height()) / 4;
+        let start = self.get_start();
+
+        render_grid_style(
+            tiles,
+            renderer,
+            styles.get_grid_style(),
+            label_size,
+            get_label_offset(self.label_margin, tile_size),
+            |x, y| {
+                start
+                    + Point2d::new(
+                        (x * tile_size.width() as f32).round() as i32,
+                        (y * tile_size.height() as f32).round() as i32,
+                    )
+            },
+        );
     }
 }
 
 impl TopDownView {
     pub fn new(tile_size: Size2d) -> Self {
-        TopDownView { tile_size }
+        TopDownView {
+            tile_size,
+            label_margin: 0,
+        }
+    }
+
+    pub fn get_label_margin(&self) -> u32 {
+        self.label_margin
+    }
+
+    /// Reserves space in the image at the left & back edge of the tilemap for the labels of the grid.
+    /// Without it, the labels are rendered inside the tiles at the edge.
+    pub fn set_label_margin(&mut self, margin: u32) {
+        self.label_margin = margin;
+    }
+
+    /// Returns the point of the image, where the tilemap starts.
+    fn get_start(&self) -> Point2d {
+        Point2d::new(self.label_margin as i32, self.label_margin as i32)
     }
 
-    fn render_tiles(&self, tilemap: &Tilemap2d, renderer: &mut dyn Renderer, styles: &StyleMgr) {
+    /// Renders the floor or the solid tiles, so each [`Layer`] is rendered in one run.
+    fn render_tiles(
+        &self,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        renderer: &mut dyn Renderer,
+        styles: &StyleMgr,
+        layer: Layer,
+    ) {
         let tiles = tilemap.get_size();
-        let mut y = 0;
+        let start = self.get_start();
+        let mut y = start.y;
         let mut index = 0;
 
-        for _y in 0..tiles.height() {
-            let mut x = 0;
+        renderer.set_layer(layer);
+
+        for tile_y in 0..tiles.height() {
+            let mut x = start.x;
 
-            for _x in 0..tiles.width() {
+            for tile_x in 0..tiles.width() {
                 let tile = tilemap.get_tile(index);
+                let light = lights.get_tile_light(index);
 
                 match tile {
-                    Tile::Empty => {}
-                    Tile::Floor(id) => self.render_tile(
-                        renderer,
-                        x,
-                        y,
-                        *styles.get_floor_style(id).get_floor_color(),
-                    ),
-                    Tile::Solid(id) => {
-                        let color = styles.get_solid_style(id).get_aab_style().get_top_color();
-                        self.render_tile(renderer, x, y, *color)
+                    Tile::Floor(id) if layer == Layer::Floors => {
+                        let style = styles.get_floor_style(id);
+                        renderer.start_selection(Selection::Tile(index));
+                        renderer.set_style(StyleRef::new(StyleKind::Floor, style.get_name()));
+                        let tile = Point2d::new(tile_x as i32, tile_y as i32);
+                        self.render_floor(renderer, x, y, style, tile, light)
                     }
+                    Tile::Solid(id) if layer == Layer::Solids => {
+                        let style = styles.get_solid_style(id);
+                        let style_ref = StyleRef::new(StyleKind::Solid, style.get_name());
+                        renderer.start_selection(Selection::Tile(index));
+                        renderer.set_style(style_ref.with_face(Face::Top));
+                        let aab = style.get_tile_style(tile_x, tile_y);
+                        let color = *aab.get_top_color() * light;
+                        render_top_face(renderer, Point2d::new(x, y), self.tile_size, &aab, color)
+                    }
+                    _ => {}
                 }
 
                 x += self.tile_size.width() as i32;
@@ -96,6 +180,7 @@ impl TopDownView {
     fn render_horizontal_borders(
         &self,
         tilemap: &Tilemap2d,
+        lights: &LightMap,
         nodes: &[Node],
         renderer: &mut dyn Renderer,
         styles: &StyleMgr,
@@ -103,11 +188,14 @@ impl TopDownView {
         let size = get_horizontal_borders_size(tilemap.get_size());
         let borders = tilemap.get_horizontal_borders();
 
-        let mut y = 0i32;
+        renderer.set_layer(Layer::Walls);
+
+        let start = self.get_start();
+        let mut y = start.y;
         let mut index = 0;
 
         for row in 0..size.height() {
-            let mut x = 0;
+            let mut x = start.x;
 
             for _x in 0..size.width() {
                 match &borders[index] {
@@ -117,12 +205,24 @@ impl TopDownView {
                         let thickness = style.get_thickness();
                         let (start, length) =
                             calculate_horizontal_border(nodes, self.tile_size.width(), index, row);
-
-                        renderer.render_rectangle(
-                            x + start,
-                            y - thickness as i32 / 2,
-                            Size2d::new(length, thickness),
-                            *style.get_aab_style().get_top_color(),
+                        let (tile_index, side) =
+                            get_tile_of_horizontal_border(tilemap.get_size(), index);
+                        let light = lights.get_wall_lights(tile_index, side).top;
+
+                        renderer.start_selection(Selection::Border(tile_index, side));
+                        renderer.set_style(
+                            StyleRef::new(StyleKind::Wall, style.get_name()).with_face(Face::Top),
+                        );
+
+                        let offset = style.get_start_offset().ceil() as i32;
+                        let start = Point2d::new(x + start, y + offset);
+                        let size = Size2d::new(length, thickness);
+                        render_top_face(
+                            renderer,
+                            start,
+                            size,
+                            style.get_aab_style(),
+                            *style.get_aab_style().get_top_color() * light,
                         )
                     }
                 }
@@ -138,6 +238,7 @@ impl TopDownView {
     fn render_vertical_borders(
         &self,
         tilemap: &Tilemap2d,
+        lights: &LightMap,
         nodes: &[Node],
         renderer: &mut dyn Renderer,
         styles: &StyleMgr,
@@ -145,11 +246,14 @@ impl TopDownView {
         let size = get_vertical_borders_size(tilemap.get_size());
         let borders = tilemap.get_vertical_borders();
 
-        let mut y = 0;
+        renderer.set_layer(Layer::Walls);
+
+        let start = self.get_start();
+        let mut y = start.y;
         let mut index = 0;
 
         for _y in 0..size.height() {
-            let mut x = 0i32;
+            let mut x = start.x;
 
             for _x in 0..size.width() {
                 match &borders[index] {
@@ -159,12 +263,24 @@ impl TopDownView {
                         let thickness = style.get_thickness();
                         let (start, length) =
                             calculate_vertical_border(nodes, self.tile_size.width(), size, index);
-
-                        renderer.render_rectangle(
-                            x - thickness as i32 / 2,
-                            y + start,
-                            Size2d::new(thickness, length),
-                            *style.get_aab_style().get_top_color(),
+                        let (tile_index, side) =
+                            get_tile_of_vertical_border(tilemap.get_size(), index);
+                        let light = lights.get_wall_lights(tile_index, side).top;
+
+                        renderer.start_selection(Selection::Border(tile_index, side));
+                        renderer.set_style(
+                            StyleRef::new(StyleKind::Wall, style.get_name()).with_face(Face::Top),
+                        );
+
+                        let offset = style.get_start_offset().ceil() as i32;
+                        let start = Point2d::new(x + offset, y + start);
+                        let size = Size2d::new(thickness, length);
+                        render_top_face(
+                            renderer,
+                            start,
+                            size,
+                            style.get_aab_style(),
+                            *style.get_aab_style().get_top_color() * light,
                         )
                     }
                 }
@@ -177,28 +293,67 @@ impl TopDownView {
         }
     }
 
-    fn render_nodes(&self, tilemap: &Tilemap2d, nodes: &[Node], renderer: &mut dyn Renderer) {
+    fn render_nodes(
+        &self,
+        tilemap: &Tilemap2d,
+        lights: &LightMap,
+        nodes: &[Node],
+        renderer: &mut dyn Renderer,
+    ) {
         let size = get_nodes_size(tilemap.get_size());
 
-        let mut y = 0;
+        renderer.set_layer(Layer::Nodes);
+
+        let start = self.get_start();
+        let mut y = start.y;
         let mut index = 0;
 
         for _y in 0..size.height() {
-            let mut x = 0i32;
+            let mut x = start.x;
 
             for _x in 0..size.width() {
-                match nodes[index] {
+                match &nodes[index] {
                     Node::NoNode => {}
                     Node::InnerNode => {}
-                    Node::OuterNode(style) => {
-                        let half = style.get_half() as i32;
-
-                        renderer.render_rectangle(
-                            x - half,
-                            y - half,
-                            Size2d::square(style.get_size()),
-                            *style.get_style().get_top_color(),
-                        )
+                    Node::OuterNode(style, offset) => {
+                        let center = Point2d::new(x, y) + *offset;
+                        renderer.start_selection(Selection::Node(index));
+                        renderer.set_style(
+                            StyleRef::new(StyleKind::Node, style.get_name()).with_face(Face::Top),
+                        );
+                        let color =
+                            *style.get_style().get_top_color() * lights.get_node_lights(index).top;
+
+                        if style.get_shape() == NodeShape::Square {
+                            let half = style.get_half() as i32;
+                            let start = Point2d::new(center.x - half, center.y - half);
+                            let size = Size2d::square(style.get_size());
+                            render_top_face(renderer, start, size, style.get_style(), color)
+                        } else {
+                            let corners = style.get_shape().get_corners(style.get_size());
+                            let points = to_points(center.x, center.y, &corners);
+                            renderer.render_polygon(&points, color);
+                            render_face_outline(renderer, &points, style.get_style())
+                        }
+                    }
+                    Node::Joint(joint) => {
+                        let light = lights.get_node_lights(index).top;
+
+                        renderer.start_selection(Selection::Node(index));
+
+                        for part in joint.get_parts() {
+                            let style = part.get_style();
+                            let points = to_points(x, y, part.get_corners());
+                            renderer.set_style(
+                                StyleRef::new(StyleKind::Wall, style.get_name())
+                                    .with_face(Face::Top),
+                            );
+                            renderer.render_polygon(
+                                &points,
+                                *style.get_aab_style().get_top_color() * light,
+                            );
+                            render_face_outline(renderer, &points, style.get_aab_style())
+                        }
                     }
                 }
 
@@ -210,19 +365,335 @@ impl TopDownView {
         }
     }
 
+    fn render_floor(
+        &self,
+        renderer: &mut dyn Renderer,
+        x: i32,
+        y: i32,
+        style: &FloorStyle,
+        tile: Point2d,
+        light: Color,
+    ) {
+        let color = style.get_tile_color(tile.x as u32, tile.y as u32) * light;
+        let start = Point2d::new(x, y);
+        let p1 = start + Point2d::new(0, self.tile_size.height() as i32);
+        let p2 = start + self.tile_size;
+        let p3 = start + Point2d::new(self.tile_size.width() as i32, 0);
+
+        match (style.get_image(), style.get_pattern()) {
+            (Some(image), _) => renderer.render_image(start, p1, p2, p3, image, color),
+            (None, Some(pattern)) => {
+                let pattern = pattern.illuminate(light);
+                renderer.render_pattern(start, p1, p2, p3, &pattern, tile, color)
+            }
+            (None, None) => self.render_tile(renderer, x, y, color),
+        }
+    }
+
     fn render_tile(&self, renderer: &mut dyn Renderer, x: i32, y: i32, color: Color) {
         renderer.render_rectangle(x, y, self.tile_size, color)
     }
 }
 
+/// Converts corners relative to a node into points of the image.
+/// Halves are rounded up, like the [start offset](crate::renderer::style::wall::WallStyle::get_start_offset) of the walls.
+fn to_points(x: i32, y: i32, corners: &[(f32, f32)]) -> Vec<Point2d> {
+    corners
+        .iter()
+        .map(|(dx, dy)| Point2d::new(x + (dx + 0.5).floor() as i32, y + (dy + 0.5).floor() as i32))
+        .collect()
+}
+
+/// Renders the top face of a box with its color or image & its outline, if the [`BoxStyle`] has one.
+fn render_top_face(
+    renderer: &mut dyn Renderer,
+    start: Point2d,
+    size: Size2d,
+    style: &BoxStyle,
+    color: Color,
+) {
+    let end = start + size;
+    let points = [
+        start,
+        Point2d::new(start.x, end.y),
+        end,
+        Point2d::new(end.x, start.y),
+    ];
+
+    match style.get_top_image() {
+        None => renderer.render_rectangle(start.x, start.y, size, color),
+        Some(image) => {
+            renderer.render_image(points[0], points[1], points[2], points[3], image, color)
+        }
+    }
+
+    render_face_outline(renderer, &points, style);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::color::{GREEN, RED, YELLOW};
+    use crate::math::side::Side;
+    use crate::renderer::recorder::Recorder;
+    use crate::renderer::style::aab::{BoxStyle, Outline};
+    use crate::renderer::style::floor::FloorStyle;
+    use crate::renderer::style::grid::{GridStyle, LabelFormat, LineStyle};
+    use crate::renderer::style::node::NodeStyle;
+    use crate::renderer::style::solid::SolidStyle;
+    use crate::renderer::style::wall::{WallAlignment, WallJoin, WallStyle};
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::tile::FloorId;
+    use crate::tilemap::tile::SolidId;
+    use crate::tilemap::visibility::Visibility;
+    use crate::tilemap::NodeId;
 
     #[test]
     fn test_get_size() {
         let viewer = TopDownView::new(Size2d::new(15, 25));
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_size(Size2d::new(2, 3), &styles),
+            Size2d::new(30, 75)
+        );
+    }
+
+    #[test]
+    fn test_label_margin() {
+        let mut viewer = TopDownView::new(Size2d::new(10, 20));
+        viewer.set_label_margin(8);
+        let tilemap = Tilemap2d::default(Size2d::new(1, 1), Tile::Floor(FloorId(0))).unwrap();
+        let grid = GridStyle::new(
+            "grid",
+            LineStyle::solid(RED, 1),
+            None,
+            Some(LabelFormat::Chess),
+            GridPosition::AboveWalls,
+        );
+        let styles = StyleMgr::without_manager(vec![], vec![], vec![], vec![], grid).unwrap();
+        let mut recorder = Recorder::default();
+
+        viewer.render(&tilemap, None, &mut recorder, &styles);
+
+        assert_eq!(
+            viewer.get_size(tilemap.get_size(), &styles),
+            Size2d::new(18, 28)
+        );
+        assert_eq!(recorder.get_rectangles(), vec![(8, 8, Size2d::new(10, 20))]);
+        assert_eq!(
+            recorder.get_texts(),
+            vec![
+                (Point2d::new(13, 4), "A".to_string()),
+                (Point2d::new(4, 18), "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_grid_is_hidden() {
+        let viewer = TopDownView::new(Size2d::square(10));
+        let tilemap = Tilemap2d::default(Size2d::new(2, 2), Tile::Floor(FloorId(0))).unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+        let mut recorder = Recorder::default();
+
+        viewer.render(&tilemap, None, &mut recorder, &styles);
+
+        assert!(recorder.get_styled_lines().is_empty());
+    }
+
+    #[test]
+    fn test_get_tile_viewport() {
+        let viewer = TopDownView::new(Size2d::new(15, 25));
+        let tiles = Size2d::new(5, 6);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        assert_eq!(
+            viewer.get_tile_viewport(tiles, Point2d::new(1, 2), Size2d::new(3, 4), &styles),
+            Viewport::new(Point2d::new(15, 50), Size2d::new(45, 100))
+        );
+    }
+
+    #[test]
+    fn test_render_outline() {
+        let viewer = TopDownView::new(Size2d::square(10));
+        let tilemap = Tilemap2d::new(
+            Size2d::new(2, 1),
+            vec![Tile::Floor(FloorId(0)), Tile::Solid(SolidId(0))],
+        )
+        .unwrap();
+        let style = BoxStyle::with_outline(RED, RED, RED, Outline::new(GREEN, 2));
+        let solid = SolidStyle::new("solid", style);
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![solid], vec![], GridStyle::default())
+                .unwrap();
+        let mut recorder = Recorder::default();
+
+        viewer.render(&tilemap, None, &mut recorder, &styles);
+
+        assert_eq!(
+            recorder.get_outlines(),
+            vec![vec![
+                Point2d::new(10, 0),
+                Point2d::new(10, 10),
+                Point2d::new(20, 10),
+                Point2d::new(20, 0),
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_pick() {
+        let viewer = TopDownView::new(Size2d::square(10));
+        let mut tilemap = Tilemap2d::default(Size2d::square(2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(0, Side::Right, Border::Wall(WallId(0)));
+        let styles = create_styles();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(5, 5), Some(Selection::Tile(0)));
+        assert_eq!(pick(15, 15), Some(Selection::Tile(3)));
+        assert_eq!(pick(10, 5), Some(Selection::Border(1, Side::Left)));
+        assert_eq!(pick(10, 9), Some(Selection::Node(4)));
+        assert_eq!(pick(25, 5), None);
+    }
+
+    #[test]
+    fn test_pick_round_node() {
+        let viewer = TopDownView::new(Size2d::square(20));
+        let mut tilemap = Tilemap2d::default(Size2d::square(2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(0, Side::Right, Border::Wall(WallId(0)));
+        let node =
+            NodeStyle::with_shape("node", BoxStyle::shaded(RED), NodeShape::Circle, 10, None);
+        let wall = WallStyle::new("wall", BoxStyle::shaded(YELLOW), NodeId(0), 2);
+        let styles = StyleMgr::without_manager(
+            vec![FloorStyle::new("floor", GREEN)],
+            vec![node],
+            vec![],
+            vec![wall],
+            GridStyle::default(),
+        )
+        .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(20, 17), Some(Selection::Node(4)));
+        assert_eq!(pick(23, 17), Some(Selection::Node(4)));
+        assert_eq!(pick(24, 16), Some(Selection::Tile(1)));
+        assert_eq!(pick(20, 14), Some(Selection::Border(1, Side::Left)));
+    }
+
+    #[test]
+    fn test_pick_filled_corner() {
+        let viewer = TopDownView::new(Size2d::square(20));
+        let mut tilemap = Tilemap2d::default(Size2d::square(2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(3, Side::Back, Border::Wall(WallId(0)));
+        tilemap.set_border(3, Side::Left, Border::Wall(WallId(0)));
+        let wall =
+            WallStyle::new("wall", BoxStyle::shaded(YELLOW), NodeId(0), 4).joined(WallJoin::Filled);
+        let styles = StyleMgr::without_manager(
+            vec![FloorStyle::new("floor", GREEN)],
+            vec![],
+            vec![],
+            vec![wall],
+            GridStyle::default(),
+        )
+        .unwrap();
+
+        let pick = |x, y| viewer.pick(&tilemap, None, Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(18, 18), Some(Selection::Node(4)));
+        assert_eq!(pick(21, 21), Some(Selection::Node(4)));
+        assert_eq!(pick(22, 18), Some(Selection::Border(3, Side::Back)));
+        assert_eq!(pick(18, 22), Some(Selection::Border(3, Side::Left)));
+        assert_eq!(pick(17, 17), Some(Selection::Tile(0)));
+    }
+
+    #[test]
+    fn test_pick_aligned_walls() {
+        let viewer = TopDownView::new(Size2d::square(20));
+        let mut tilemap = Tilemap2d::default(Size2d::new(1, 2), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(1, Side::Back, Border::Wall(WallId(0)));
+        let pick = |alignment, x, y| {
+            let wall = WallStyle::new("wall", BoxStyle::shaded(YELLOW), NodeId(0), 4);
+            let styles = StyleMgr::without_manager(
+                vec![FloorStyle::new("floor", GREEN)],
+                vec![NodeStyle::new("node", BoxStyle::shaded(RED), 4)],
+                vec![],
+                vec![wall.aligned(alignment)],
+                GridStyle::default(),
+            )
+            .unwrap();
+            viewer.pick(&tilemap, None, Point2d::new(x, y), &styles)
+        };
+        let wall = Some(Selection::Border(1, Side::Back));
+
+        assert_eq!(
+            pick(WallAlignment::Centered, 10, 17),
+            Some(Selection::Tile(0))
+        );
+        assert_eq!(pick(WallAlignment::Centered, 10, 21), wall);
+        assert_eq!(pick(WallAlignment::BackLeft, 10, 17), wall);
+        assert_eq!(
+            pick(WallAlignment::BackLeft, 10, 20),
+            Some(Selection::Tile(1))
+        );
+        assert_eq!(
+            pick(WallAlignment::FrontRight, 10, 19),
+            Some(Selection::Tile(0))
+        );
+        assert_eq!(pick(WallAlignment::FrontRight, 10, 23), wall);
+        // the nodes at the ends move with the wall
+        assert_eq!(
+            pick(WallAlignment::BackLeft, 1, 16),
+            Some(Selection::Node(2))
+        );
+        assert_eq!(
+            pick(WallAlignment::BackLeft, 1, 21),
+            Some(Selection::Tile(1))
+        );
+    }
+
+    #[test]
+    fn test_pick_with_visibility() {
+        let viewer = TopDownView::new(Size2d::square(10));
+        let mut tilemap = Tilemap2d::default(Size2d::new(3, 1), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(0, Side::Right, Border::Wall(WallId(0)));
+        tilemap.set_border(1, Side::Right, Border::Wall(WallId(0)));
+        let mask = VisibilityMask::new(
+            Size2d::new(3, 1),
+            vec![Visibility::Seen, Visibility::Unseen, Visibility::Unseen],
+        )
+        .unwrap();
+        let styles = create_styles();
+
+        let pick = |x, y| viewer.pick(&tilemap, Some(&mask), Point2d::new(x, y), &styles);
+
+        assert_eq!(pick(5, 5), Some(Selection::Tile(0)));
+        assert_eq!(pick(10, 5), Some(Selection::Border(1, Side::Left)));
+        assert_eq!(pick(15, 5), None);
+        assert_eq!(pick(20, 5), None);
+    }
 
-        assert_eq!(viewer.get_size(Size2d::new(2, 3)), Size2d::new(30, 75));
+    fn create_styles() -> StyleMgr {
+        StyleMgr::without_manager(
+            vec![FloorStyle::new("floor", GREEN)],
+            vec![NodeStyle::new("node", BoxStyle::shaded(RED), 4)],
+            vec![],
+            vec![WallStyle::new(
+                "wall",
+                BoxStyle::shaded(YELLOW),
+                NodeId(0),
+                2,
+            )],
+            GridStyle::default(),
+        )
+        .unwrap()
     }
 }
@@ -0,0 +1,396 @@
+use crate::math::color::Color;
+use crate::math::point2d::Point2d;
+use crate::math::size2d::Size2d;
+use crate::port::layer::Layer;
+use crate::port::renderer::Renderer;
+use crate::port::style::StyleRef;
+use crate::renderer::style::aab::Outline;
+use crate::renderer::style::grid::LineStyle;
+use crate::renderer::style::image::Image;
+use crate::renderer::style::pattern::Pattern;
+use crate::tilemap::selection::Selection;
+
+/// The smallest zoom of a [`Viewport`], so it never divides by zero or flips the image.
+pub const MIN_ZOOM: f32 = 0.01;
+
+/// A camera showing only a part of the rendered image.
+///
+/// The visible area starts at a point of the full image & is scaled by the zoom,
+/// so that it fills the output image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    start: Point2d,
+    size: Size2d,
+    zoom: f32,
+}
+
+impl Viewport {
+    /// Returns a viewport without zoom.
+    pub fn new(start: Point2d, size: Size2d) -> Self {
+        Self::with_zoom(start, size, 1.0)
+    }
+
+    /// Returns a viewport with a zoom factor. The size is the size of the output image.
+    /// The zoom is clamped to [`MIN_ZOOM`], which also replaces NaN.
+    pub fn with_zoom(start: Point2d, size: Size2d, zoom: f32) -> Self {
+        Viewport {
+            start,
+            size,
+            zoom: zoom.max(MIN_ZOOM),
+        }
+    }
+
+    /// Returns the point of the full image shown at the origin of the output.
+    pub fn get_start(&self) -> Point2d {
+        self.start
+    }
+
+    /// Returns the size of the output image.
+    pub fn get_size(&self) -> Size2d {
+        self.size
+    }
+
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Moves the viewport over the full image.
+    ///
+    /// ```
+    ///# use tilemap::math::point2d::Point2d;
+    ///# use tilemap::math::size2d::Size2d;
+    ///# use tilemap::renderer::viewport::Viewport;
+    /// let mut viewport = Viewport::new(Point2d::new(10, 20), Size2d::new(100, 50));
+    /// viewport.pan(Point2d::new(5, -10));
+    ///
+    /// assert_eq!(viewport.get_start(), Point2d::new(15, 10));
+    /// ```
+    pub fn pan(&mut self, delta: Point2d) {
+        self.start = self.start + delta;
+    }
+
+    /// Changes the zoom factor, which is clamped to [`MIN_ZOOM`] like for [`Viewport::with_zoom`].
+    ///
+    /// ```
+    ///# use tilemap::math::point2d::Point2d;
+    ///# use tilemap::math::size2d::Size2d;
+    ///# use tilemap::renderer::viewport::{Viewport, MIN_ZOOM};
+    /// let mut viewport = Viewport::new(Point2d::new(10, 20), Size2d::new(100, 50));
+    /// viewport.set_zoom(-2.0);
+    ///
+    /// assert_eq!(viewport.get_zoom(), MIN_ZOOM);
+    /// ```
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(MIN_ZOOM);
+    }
+
+    /// Converts a point of the full image to the output image.
+    ///
+    /// ```
+    ///# use tilemap::math::point2d::Point2d;
+    ///# use tilemap::math::size2d::Size2d;
+    ///# use tilemap::renderer::viewport::Viewport;
+    /// let viewport = Viewport::with_zoom(Point2d::new(10, 20), Size2d::new(100, 50), 2.0);
+    ///
+    /// assert_eq!(viewport.to_output(Point2d::new(15, 30)), Point2d::new(10, 20));
+    /// ```
+    pub fn to_output(&self, point: Point2d) -> Point2d {
+        Point2d::new(
+            ((point.x - self.start.x) as f32 * self.zoom).round() as i32,
+            ((point.y - self.start.y) as f32 * self.zoom).round() as i32,
+        )
+    }
+
+    /// Converts a point of the output image to the full image, e.g. to pick the selected tile.
+    ///
+    /// ```
+    ///# use tilemap::math::point2d::Point2d;
+    ///# use tilemap::math::size2d::Size2d;
+    ///# use tilemap::renderer::viewport::Viewport;
+    /// let viewport = Viewport::with_zoom(Point2d::new(10, 20), Size2d::new(100, 50), 2.0);
+    ///
+    /// assert_eq!(viewport.to_image(Point2d::new(10, 20)), Point2d::new(15, 30));
+    /// ```
+    pub fn to_image(&self, point: Point2d) -> Point2d {
+        Point2d::new(
+            self.start.x + (point.x as f32 / self.zoom).floor() as i32,
+            self.start.y + (point.y as f32 / self.zoom).floor() as i32,
+        )
+    }
+
+    /// Is any part of the axis aligned bounding box inside the viewport?
+    pub fn is_visible(&self, min: Point2d, max: Point2d) -> bool {
+        let end_x = self.start.x as f32 + self.size.width() as f32 / self.zoom;
+        let end_y = self.start.y as f32 + self.size.height() as f32 / self.zoom;
+
+        max.x >= self.start.x
+            && max.y >= self.start.y
+            && (min.x as f32) < end_x
+            && (min.y as f32) < end_y
+    }
+
+    fn is_polygon_visible(&self, points: &[Point2d]) -> bool {
+        let min = Point2d::new(
+            points.iter().map(|p| p.x).min().unwrap_or_default(),
+            points.iter().map(|p| p.y).min().unwrap_or_default(),
+        );
+        let max = Point2d::new(
+            points.iter().map(|p| p.x).max().unwrap_or_default(),
+            points.iter().map(|p| p.y).max().unwrap_or_default(),
+        );
+
+        self.is_visible(min, max)
+    }
+}
+
+/// A [`Renderer`] that culls everything outside the [`Viewport`]
+/// & transforms the rest, so that the output starts at the origin.
+pub struct ViewportRenderer<'a> {
+    viewport: Viewport,
+    renderer: &'a mut dyn Renderer,
+}
+
+impl<'a> ViewportRenderer<'a> {
+    pub fn new(viewport: Viewport, renderer: &'a mut dyn Renderer) -> Self {
+        ViewportRenderer { viewport, renderer }
+    }
+}
+
+impl<'a> Renderer for ViewportRenderer<'a> {
+    fn render_rectangle(&mut self, x: i32, y: i32, size: Size2d, color: Color) {
+        let start = Point2d::new(x, y);
+        let end = start + size;
+
+        if !self.viewport.is_visible(start, end - 1) {
+            return;
+        }
+
+        let start = self.viewport.to_output(start);
+        let end = self.viewport.to_output(end);
+        let size = Size2d::new((end.x - start.x) as u32, (end.y - start.y) as u32);
+
+        self.renderer
+            .render_rectangle(start.x, start.y, size, color);
+    }
+
+    fn render_transformed_rectangle(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        color: Color,
+    ) {
+        if !self.viewport.is_polygon_visible(&[p0, p1, p2, p3]) {
+            return;
+        }
+
+        self.renderer.render_transformed_rectangle(
+            self.viewport.to_output(p0),
+            self.viewport.to_output(p1),
+            self.viewport.to_output(p2),
+            self.viewport.to_output(p3),
+            color,
+        );
+    }
+
+    fn render_pattern(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        pattern: &Pattern,
+        tile: Point2d,
+        background: Color,
+    ) {
+        if !self.viewport.is_polygon_visible(&[p0, p1, p2, p3]) {
+            return;
+        }
+
+        self.renderer.render_pattern(
+            self.viewport.to_output(p0),
+            self.viewport.to_output(p1),
+            self.viewport.to_output(p2),
+            self.viewport.to_output(p3),
+            pattern,
+            tile,
+            background,
+        );
+    }
+
+    fn render_image(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        image: &Image,
+        background: Color,
+    ) {
+        if !self.viewport.is_polygon_visible(&[p0, p1, p2, p3]) {
+            return;
+        }
+
+        self.renderer.render_image(
+            self.viewport.to_output(p0),
+            self.viewport.to_output(p1),
+            self.viewport.to_output(p2),
+            self.viewport.to_output(p3),
+            image,
+            background,
+        );
+    }
+
+    fn render_polygon(&mut self, points: &[Point2d], color: Color) {
+        if !self.viewport.is_polygon_visible(points) {
+            return;
+        }
+
+        let points: Vec<Point2d> = points
+            .iter()
+            .map(|point| self.viewport.to_output(*point))
+            .collect();
+
+        self.renderer.render_polygon(&points, color);
+    }
+
+    fn render_shadow(&mut self, points: &[Point2d], color: Color, opacity: f32) {
+        if !self.viewport.is_polygon_visible(points) {
+            return;
+        }
+
+        let points: Vec<Point2d> = points
+            .iter()
+            .map(|point| self.viewport.to_output(*point))
+            .collect();
+
+        self.renderer.render_shadow(&points, color, opacity);
+    }
+
+    fn render_line(&mut self, p0: Point2d, p1: Point2d, color: Color) {
+        if !self.viewport.is_polygon_visible(&[p0, p1]) {
+            return;
+        }
+
+        self.renderer.render_line(
+            self.viewport.to_output(p0),
+            self.viewport.to_output(p1),
+            color,
+        );
+    }
+
+    /// Keeps the width of the line independent of the zoom.
+    fn render_styled_line(&mut self, p0: Point2d, p1: Point2d, style: &LineStyle) {
+        if !self.viewport.is_polygon_visible(&[p0, p1]) {
+            return;
+        }
+
+        self.renderer.render_styled_line(
+            self.viewport.to_output(p0),
+            self.viewport.to_output(p1),
+            style,
+        );
+    }
+
+    /// Keeps the width of the outline independent of the zoom.
+    fn render_outline(&mut self, points: &[Point2d], outline: &Outline) {
+        if !self.viewport.is_polygon_visible(points) {
+            return;
+        }
+
+        let points: Vec<Point2d> = points
+            .iter()
+            .map(|point| self.viewport.to_output(*point))
+            .collect();
+
+        self.renderer.render_outline(&points, outline);
+    }
+
+    fn render_text(&mut self, center: Point2d, text: &str, size: u32, color: Color) {
+        if !self.viewport.is_polygon_visible(&[center]) {
+            return;
+        }
+
+        let size = (size as f32 * self.viewport.zoom).round() as u32;
+
+        self.renderer
+            .render_text(self.viewport.to_output(center), text, size, color);
+    }
+
+    fn start_selection(&mut self, selection: Selection) {
+        self.renderer.start_selection(selection);
+    }
+
+    fn set_layer(&mut self, layer: Layer) {
+        self.renderer.set_layer(layer);
+    }
+
+    fn set_style(&mut self, style: StyleRef) {
+        self.renderer.set_style(style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::PINK;
+    use crate::renderer::recorder::Recorder;
+
+    #[test]
+    fn test_is_visible() {
+        let viewport = Viewport::new(Point2d::new(10, 20), Size2d::new(30, 40));
+
+        assert!(viewport.is_visible(Point2d::new(0, 0), Point2d::new(10, 20)));
+        assert!(viewport.is_visible(Point2d::new(39, 59), Point2d::new(50, 70)));
+        assert!(!viewport.is_visible(Point2d::new(0, 0), Point2d::new(9, 100)));
+        assert!(!viewport.is_visible(Point2d::new(0, 0), Point2d::new(100, 19)));
+        assert!(!viewport.is_visible(Point2d::new(40, 0), Point2d::new(100, 100)));
+        assert!(!viewport.is_visible(Point2d::new(0, 60), Point2d::new(100, 100)));
+    }
+
+    #[test]
+    fn test_is_visible_with_zoom() {
+        let viewport = Viewport::with_zoom(Point2d::new(10, 20), Size2d::new(30, 40), 2.0);
+
+        assert!(viewport.is_visible(Point2d::new(24, 39), Point2d::new(50, 70)));
+        assert!(!viewport.is_visible(Point2d::new(25, 0), Point2d::new(100, 100)));
+        assert!(!viewport.is_visible(Point2d::new(0, 40), Point2d::new(100, 100)));
+    }
+
+    #[test]
+    fn test_clamp_invalid_zoom() {
+        let start = Point2d::new(10, 20);
+        let size = Size2d::new(30, 40);
+
+        for zoom in [0.0, -1.0, f32::NAN] {
+            let viewport = Viewport::with_zoom(start, size, zoom);
+
+            assert_eq!(viewport.get_zoom(), MIN_ZOOM);
+            assert_eq!(
+                viewport.to_image(Point2d::new(1, 1)),
+                Point2d::new(110, 120)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cull_and_transform_rectangles() {
+        let viewport = Viewport::with_zoom(Point2d::new(10, 20), Size2d::new(30, 40), 2.0);
+        let mut recorder = Recorder::default();
+        let mut renderer = ViewportRenderer::new(viewport, &mut recorder);
+
+        renderer.render_rectangle(0, 0, Size2d::square(10), PINK);
+        renderer.render_rectangle(5, 15, Size2d::square(10), PINK);
+        renderer.render_rectangle(20, 30, Size2d::new(5, 10), PINK);
+        renderer.render_rectangle(25, 30, Size2d::new(5, 10), PINK);
+
+        assert_eq!(
+            recorder.get_rectangles(),
+            vec![
+                (-10, -10, Size2d::square(20)),
+                (20, 20, Size2d::new(10, 20))
+            ]
+        );
+    }
+}
@@ -1,6 +1,20 @@
+use crate::math::side::Side;
 use crate::math::size2d::Size2d;
+use crate::utils::resource::ResourceId;
 
-pub type WallId = usize;
+/// The id of a [`WallStyle`](crate::renderer::style::wall::WallStyle).
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct WallId(pub usize);
+
+impl ResourceId for WallId {
+    fn from_index(index: usize) -> Self {
+        WallId(index)
+    }
+
+    fn get_index(&self) -> usize {
+        self.0
+    }
+}
 
 /// The border between 2 [`tiles`](crate::tilemap::tile::Tile).
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -50,16 +64,58 @@ pub fn right_of_tile(size: Size2d, tile_index: usize) -> usize {
     left_of_tile(size, tile_index) + 1
 }
 
+/// Returns the index of a [`tile`](crate::tilemap::tile::Tile) & the [`side`](Side) of the tile for a horizontal [`Border`].
+pub fn get_tile_of_horizontal_border(size: Size2d, border_index: usize) -> (usize, Side) {
+    if border_index < size.count() {
+        (border_index, Side::Back)
+    } else {
+        (border_index - size.width() as usize, Side::Front)
+    }
+}
+
+/// Returns the index of a [`tile`](crate::tilemap::tile::Tile) & the [`side`](Side) of the tile for a vertical [`Border`].
+pub fn get_tile_of_vertical_border(size: Size2d, border_index: usize) -> (usize, Side) {
+    let y = border_index / (size.width() as usize + 1);
+    let tile_index = border_index - y;
+
+    if border_index % (size.width() as usize + 1) < size.width() as usize {
+        (tile_index, Side::Left)
+    } else {
+        (tile_index - 1, Side::Right)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use Border::*;
 
-    const WALL: Border = Wall(42);
+    const WALL: Border = Wall(WallId(42));
 
     #[test]
     fn test_get_wall_style() {
         assert_eq!(NoBorder.get_wall_style(), None);
-        assert_eq!(WALL.get_wall_style(), Some(42));
+        assert_eq!(WALL.get_wall_style(), Some(WallId(42)));
+    }
+
+    #[test]
+    fn test_get_tile_of_horizontal_border() {
+        let size = Size2d::new(2, 3);
+
+        assert_eq!(get_tile_of_horizontal_border(size, 0), (0, Side::Back));
+        assert_eq!(get_tile_of_horizontal_border(size, 5), (5, Side::Back));
+        assert_eq!(get_tile_of_horizontal_border(size, 6), (4, Side::Front));
+        assert_eq!(get_tile_of_horizontal_border(size, 7), (5, Side::Front));
+    }
+
+    #[test]
+    fn test_get_tile_of_vertical_border() {
+        let size = Size2d::new(2, 3);
+
+        assert_eq!(get_tile_of_vertical_border(size, 0), (0, Side::Left));
+        assert_eq!(get_tile_of_vertical_border(size, 1), (1, Side::Left));
+        assert_eq!(get_tile_of_vertical_border(size, 2), (1, Side::Right));
+        assert_eq!(get_tile_of_vertical_border(size, 3), (2, Side::Left));
+        assert_eq!(get_tile_of_vertical_border(size, 8), (5, Side::Right));
     }
 }
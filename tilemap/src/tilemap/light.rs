@@ -0,0 +1,70 @@
+use crate::math::color::Color;
+use crate::math::point2d::Point2d;
+
+/// A point light in the center of a [`tile`](crate::tilemap::tile::Tile), like a torch.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Light {
+    /// The x & y coordinates of the tile.
+    position: Point2d,
+    color: Color,
+    /// The intensity in percent.
+    intensity: u32,
+    /// The distance in tiles, at which the light fades out.
+    radius: u32,
+}
+
+impl Light {
+    pub fn new(position: Point2d, color: Color, intensity: u32, radius: u32) -> Self {
+        Light {
+            position,
+            color,
+            intensity,
+            radius,
+        }
+    }
+
+    pub fn get_position(&self) -> Point2d {
+        self.position
+    }
+
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    pub fn get_intensity(&self) -> u32 {
+        self.intensity
+    }
+
+    pub fn get_radius(&self) -> u32 {
+        self.radius
+    }
+}
+
+/// The [`lights`](Light) of a [`tilemap`](crate::tilemap::tilemap2d::Tilemap2d)
+/// & the ambient light reaching every tile.
+///
+/// The light changes the colors per tile, so renderers using a color per style, like the css classes of an svg,
+/// can only reuse it for tiles with the same light.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lighting {
+    ambient: Color,
+    lights: Vec<Light>,
+}
+
+impl Lighting {
+    pub fn new(ambient: Color, lights: Vec<Light>) -> Self {
+        Lighting { ambient, lights }
+    }
+
+    pub fn get_ambient(&self) -> Color {
+        self.ambient
+    }
+
+    pub fn get_lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+}
@@ -1,6 +1,23 @@
+use crate::utils::resource::ResourceId;
+
 pub mod border;
+pub mod light;
 pub mod node;
+pub mod selection;
 pub mod tile;
 pub mod tilemap2d;
+pub mod visibility;
+
+/// The id of a [`NodeStyle`](crate::renderer::style::node::NodeStyle).
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NodeId(pub usize);
+
+impl ResourceId for NodeId {
+    fn from_index(index: usize) -> Self {
+        NodeId(index)
+    }
 
-pub type NodeId = usize;
+    fn get_index(&self) -> usize {
+        self.0
+    }
+}
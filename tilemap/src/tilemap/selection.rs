@@ -0,0 +1,12 @@
+use crate::math::side::Side;
+
+/// Identifies an element of the [`tilemap`](crate::tilemap::tilemap2d::Tilemap2d).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Selection {
+    /// A [`tile`](crate::tilemap::tile::Tile) defined by its index.
+    Tile(usize),
+    /// A [`border`](crate::tilemap::border::Border) defined by the index of a tile & the [`side`](Side) of that tile.
+    Border(usize, Side),
+    /// A [`node`](crate::renderer::node::Node) defined by its index.
+    Node(usize),
+}
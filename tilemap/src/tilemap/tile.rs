@@ -1,5 +1,32 @@
-pub type FloorId = usize;
-pub type SolidId = usize;
+use crate::utils::resource::ResourceId;
+
+/// The id of a [`FloorStyle`](crate::renderer::style::floor::FloorStyle).
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FloorId(pub usize);
+
+impl ResourceId for FloorId {
+    fn from_index(index: usize) -> Self {
+        FloorId(index)
+    }
+
+    fn get_index(&self) -> usize {
+        self.0
+    }
+}
+
+/// The id of a [`SolidStyle`](crate::renderer::style::solid::SolidStyle).
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SolidId(pub usize);
+
+impl ResourceId for SolidId {
+    fn from_index(index: usize) -> Self {
+        SolidId(index)
+    }
+
+    fn get_index(&self) -> usize {
+        self.0
+    }
+}
 
 /// A space in the [`tilemap`](crate::tilemap::tilemap2d::Tilemap2d).
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
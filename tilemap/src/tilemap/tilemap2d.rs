@@ -4,6 +4,7 @@ use crate::tilemap::border::{
     behind_tile, get_horizontal_borders_size, get_vertical_borders_size, in_front_of_tile,
     left_of_tile, right_of_tile, Border,
 };
+use crate::tilemap::light::Lighting;
 use crate::tilemap::node::get_nodes_size;
 use crate::tilemap::tile::Tile;
 use anyhow::{bail, Result};
@@ -21,6 +22,8 @@ pub struct Tilemap2d {
     horizontal_borders: Vec<Border>,
     /// The [`borders`](Border) to the left & right of each [`Tile`].
     vertical_borders: Vec<Border>,
+    /// The optional [`lighting`](Lighting). The tilemap is fully lit without it.
+    lighting: Option<Lighting>,
 }
 
 impl Tilemap2d {
@@ -61,6 +64,7 @@ impl Tilemap2d {
             tiles,
             horizontal_borders,
             vertical_borders,
+            lighting: None,
         })
     }
 
@@ -82,7 +86,7 @@ impl Tilemap2d {
         self.tiles[index] = tile;
     }
 
-    /// Borders
+    // Borders
 
     pub fn get_horizontal_borders(&self) -> &Vec<Border> {
         &self.horizontal_borders
@@ -120,6 +124,16 @@ impl Tilemap2d {
         };
     }
 
+    // lighting
+
+    pub fn get_lighting(&self) -> Option<&Lighting> {
+        self.lighting.as_ref()
+    }
+
+    pub fn set_lighting(&mut self, lighting: Option<Lighting>) {
+        self.lighting = lighting;
+    }
+
     // nodes
 
     /// Returns the [`border`](Border) on a specific [`side`](Side) of a node.
@@ -172,6 +186,8 @@ impl Tilemap2d {
 mod tests {
     use super::*;
     use crate::math::size2d::Size2d;
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::tile::{FloorId, SolidId};
     use Border::{NoBorder, Wall};
     use Tile::{Empty, Floor, Solid};
 
@@ -192,8 +208,8 @@ mod tests {
 
         assert_eq!(tilemap.get_size(), size);
 
-        for i in 0..6 {
-            assert_eq!(tilemap.get_tile(i), tiles[i]);
+        for (i, tile) in tiles.iter().enumerate() {
+            assert_eq!(tilemap.get_tile(i), *tile);
         }
     }
 
@@ -213,9 +229,9 @@ mod tests {
         let size = Size2d::new(2, 3);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_tile(0, Floor(1));
-        tilemap.set_tile(2, Solid(3));
-        tilemap.set_tile(4, Floor(4));
+        tilemap.set_tile(0, Floor(FloorId(1)));
+        tilemap.set_tile(2, Solid(SolidId(3)));
+        tilemap.set_tile(4, Floor(FloorId(4)));
 
         assert_eq!(tilemap.get_size(), size);
         assert_eq!(tilemap.get_tiles(), &create_tiles());
@@ -235,15 +251,15 @@ mod tests {
         let size = Size2d::new(2, 3);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(5, Back, Wall(1));
-        tilemap.set_border(5, Left, Wall(2));
-        tilemap.set_border(5, Front, Wall(3));
-        tilemap.set_border(5, Right, Wall(4));
+        tilemap.set_border(5, Back, Wall(WallId(1)));
+        tilemap.set_border(5, Left, Wall(WallId(2)));
+        tilemap.set_border(5, Front, Wall(WallId(3)));
+        tilemap.set_border(5, Right, Wall(WallId(4)));
 
-        assert_eq!(tilemap.get_border(5, Back), Wall(1));
-        assert_eq!(tilemap.get_border(5, Left), Wall(2));
-        assert_eq!(tilemap.get_border(5, Front), Wall(3));
-        assert_eq!(tilemap.get_border(5, Right), Wall(4));
+        assert_eq!(tilemap.get_border(5, Back), Wall(WallId(1)));
+        assert_eq!(tilemap.get_border(5, Left), Wall(WallId(2)));
+        assert_eq!(tilemap.get_border(5, Front), Wall(WallId(3)));
+        assert_eq!(tilemap.get_border(5, Right), Wall(WallId(4)));
     }
 
     #[test]
@@ -252,7 +268,7 @@ mod tests {
         let size = Size2d::new(2, 3);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(6, Back, Wall(1));
+        tilemap.set_border(6, Back, Wall(WallId(1)));
     }
 
     #[test]
@@ -260,20 +276,20 @@ mod tests {
         let size = Size2d::new(3, 3);
         let mut tilemap = Tilemap2d::default(size, Empty).unwrap();
 
-        tilemap.set_border(4, Back, Wall(1));
-        tilemap.set_border(4, Left, Wall(2));
+        tilemap.set_border(4, Back, Wall(WallId(1)));
+        tilemap.set_border(4, Left, Wall(WallId(2)));
 
         assert_eq!(tilemap.get_border_at_node(5, Back), NoBorder);
         assert_eq!(tilemap.get_border_at_node(5, Left), NoBorder);
-        assert_eq!(tilemap.get_border_at_node(5, Front), Wall(2));
-        assert_eq!(tilemap.get_border_at_node(5, Right), Wall(1));
+        assert_eq!(tilemap.get_border_at_node(5, Front), Wall(WallId(2)));
+        assert_eq!(tilemap.get_border_at_node(5, Right), Wall(WallId(1)));
 
         assert_eq!(tilemap.get_border_at_node(6, Back), NoBorder);
-        assert_eq!(tilemap.get_border_at_node(6, Left), Wall(1));
+        assert_eq!(tilemap.get_border_at_node(6, Left), Wall(WallId(1)));
         assert_eq!(tilemap.get_border_at_node(6, Front), NoBorder);
         assert_eq!(tilemap.get_border_at_node(6, Right), NoBorder);
 
-        assert_eq!(tilemap.get_border_at_node(9, Back), Wall(2));
+        assert_eq!(tilemap.get_border_at_node(9, Back), Wall(WallId(2)));
         assert_eq!(tilemap.get_border_at_node(9, Left), NoBorder);
         assert_eq!(tilemap.get_border_at_node(9, Front), NoBorder);
         assert_eq!(tilemap.get_border_at_node(9, Right), NoBorder);
@@ -294,6 +310,13 @@ mod tests {
     }
 
     fn create_tiles() -> Vec<Tile> {
-        vec![Floor(1), Empty, Solid(3), Empty, Floor(4), Empty]
+        vec![
+            Floor(FloorId(1)),
+            Empty,
+            Solid(SolidId(3)),
+            Empty,
+            Floor(FloorId(4)),
+            Empty,
+        ]
     }
 }
@@ -0,0 +1,210 @@
+use crate::math::color::{Color, BLACK, WHITE};
+use crate::math::side::Side;
+use crate::math::size2d::Size2d;
+use crate::tilemap::border::{get_tile_of_horizontal_border, get_tile_of_vertical_border, Border};
+use crate::tilemap::tile::Tile;
+use crate::tilemap::tilemap2d::Tilemap2d;
+use anyhow::{bail, Result};
+
+/// How much the viewer knows about a [`tile`](Tile), e.g. for a fog of war.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Visibility {
+    /// The tile was never seen & is not rendered.
+    Unseen,
+    /// The tile was seen before & is rendered dimmed.
+    Seen,
+    /// The tile is currently visible.
+    Visible,
+}
+
+impl Visibility {
+    /// Returns the light, that modulates the colors of a tile.
+    pub fn get_light(&self) -> Color {
+        match self {
+            Visibility::Unseen => BLACK,
+            Visibility::Seen => Color::gray(128),
+            Visibility::Visible => WHITE,
+        }
+    }
+}
+
+/// The [`Visibility`] of each [`tile`](Tile) of a [`tilemap`](Tilemap2d).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VisibilityMask {
+    size: Size2d,
+    tiles: Vec<Visibility>,
+}
+
+impl VisibilityMask {
+    /// Returns a mask of the desired [`size`](Size2d) with the same visibility for all tiles.
+    pub fn default(size: Size2d, visibility: Visibility) -> Result<VisibilityMask> {
+        Self::new(size, vec![visibility; size.count()])
+    }
+
+    /// Returns a mask with the desired visibilities, if their number matches the [`size`](Size2d).
+    pub fn new(size: Size2d, tiles: Vec<Visibility>) -> Result<VisibilityMask> {
+        if size.count() == 0 {
+            bail!("The visibility mask has a size of 0!");
+        } else if size.count() != tiles.len() {
+            bail!("Size and number of tiles don't match!");
+        }
+
+        Ok(VisibilityMask { size, tiles })
+    }
+
+    pub fn get_size(&self) -> Size2d {
+        self.size
+    }
+
+    pub fn get_visibility(&self, index: usize) -> Visibility {
+        self.tiles[index]
+    }
+
+    pub fn set_visibility(&mut self, index: usize, visibility: Visibility) {
+        self.tiles[index] = visibility;
+    }
+
+    /// Returns a copy of the tilemap without anything the viewer doesn't know about.
+    /// Unseen tiles become empty & borders are removed, unless a tile next to them is seen.
+    /// Nodes are calculated from the remaining walls, so they don't leak the hidden layout either.
+    ///
+    /// Fails, if the size of the mask doesn't match the tilemap.
+    pub fn hide_unseen(&self, tilemap: &Tilemap2d) -> Result<Tilemap2d> {
+        let size = tilemap.get_size();
+
+        if size != self.size {
+            bail!(
+                "The visibility mask doesn't match the tilemap of size {:?}!",
+                size
+            );
+        }
+
+        let tiles = tilemap
+            .get_tiles()
+            .iter()
+            .zip(self.tiles.iter())
+            .map(|(tile, visibility)| match visibility {
+                Visibility::Unseen => Tile::Empty,
+                _ => *tile,
+            })
+            .collect();
+        let horizontal_borders = tilemap
+            .get_horizontal_borders()
+            .iter()
+            .enumerate()
+            .map(|(index, border)| {
+                let (tile_index, side) = get_tile_of_horizontal_border(size, index);
+                self.hide_border(*border, tile_index, side)
+            })
+            .collect();
+        let vertical_borders = tilemap
+            .get_vertical_borders()
+            .iter()
+            .enumerate()
+            .map(|(index, border)| {
+                let (tile_index, side) = get_tile_of_vertical_border(size, index);
+                self.hide_border(*border, tile_index, side)
+            })
+            .collect();
+
+        let mut hidden =
+            Tilemap2d::with_borders(size, tiles, horizontal_borders, vertical_borders)?;
+        hidden.set_lighting(tilemap.get_lighting().cloned());
+        Ok(hidden)
+    }
+
+    /// Returns the highest visibility of the tiles on both sides of a border.
+    fn get_border_visibility(&self, tile_index: usize, side: Side) -> Visibility {
+        let x = self.size.to_x(tile_index);
+        let y = self.size.to_y(tile_index);
+        let (other_x, other_y) = match side {
+            Side::Back => (x, y - 1),
+            Side::Left => (x - 1, y),
+            Side::Front => (x, y + 1),
+            Side::Right => (x + 1, y),
+        };
+        let visibility = self.tiles[tile_index];
+
+        if other_x < 0
+            || other_y < 0
+            || other_x >= self.size.width() as i32
+            || other_y >= self.size.height() as i32
+        {
+            return visibility;
+        }
+
+        let other = self.tiles[self.size.convert_x_y(other_x as u32, other_y as u32)];
+
+        visibility.max(other)
+    }
+
+    fn hide_border(&self, border: Border, tile_index: usize, side: Side) -> Border {
+        match self.get_border_visibility(tile_index, side) {
+            Visibility::Unseen => Border::NoBorder,
+            _ => border,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tilemap::border::WallId;
+    use crate::tilemap::tile::FloorId;
+    use crate::tilemap::tile::SolidId;
+    use Visibility::*;
+
+    #[test]
+    fn test_new_with_wrong_size() {
+        assert!(VisibilityMask::new(Size2d::new(2, 3), vec![Visible; 5]).is_err());
+    }
+
+    #[test]
+    fn test_hide_unseen_with_wrong_size() {
+        let tilemap = Tilemap2d::default(Size2d::new(3, 1), Tile::Solid(SolidId(1))).unwrap();
+        let mask = VisibilityMask::default(Size2d::new(1, 3), Visible).unwrap();
+
+        assert!(mask.hide_unseen(&tilemap).is_err());
+    }
+
+    #[test]
+    fn test_hide_unseen_tiles() {
+        let tilemap = Tilemap2d::default(Size2d::new(3, 1), Tile::Solid(SolidId(1))).unwrap();
+        let mask = VisibilityMask::new(Size2d::new(3, 1), vec![Visible, Seen, Unseen]).unwrap();
+
+        let hidden = mask.hide_unseen(&tilemap).unwrap();
+
+        assert_eq!(
+            hidden.get_tiles(),
+            &vec![
+                Tile::Solid(SolidId(1)),
+                Tile::Solid(SolidId(1)),
+                Tile::Empty
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hide_unseen_borders() {
+        let mut tilemap = Tilemap2d::default(Size2d::new(3, 1), Tile::Floor(FloorId(0))).unwrap();
+        tilemap.set_border(0, Side::Left, Border::Wall(WallId(2)));
+        tilemap.set_border(1, Side::Left, Border::Wall(WallId(2)));
+        tilemap.set_border(2, Side::Left, Border::Wall(WallId(2)));
+        tilemap.set_border(2, Side::Right, Border::Wall(WallId(2)));
+        tilemap.set_border(2, Side::Back, Border::Wall(WallId(2)));
+        let mask = VisibilityMask::new(Size2d::new(3, 1), vec![Seen, Visible, Unseen]).unwrap();
+
+        let hidden = mask.hide_unseen(&tilemap).unwrap();
+
+        assert_eq!(
+            hidden.get_vertical_borders(),
+            &vec![
+                Border::Wall(WallId(2)),
+                Border::Wall(WallId(2)),
+                Border::Wall(WallId(2)),
+                Border::NoBorder
+            ]
+        );
+        assert_eq!(hidden.get_border(2, Side::Back), Border::NoBorder);
+    }
+}
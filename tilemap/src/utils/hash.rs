@@ -0,0 +1,31 @@
+/// Hashes a 2d position & a seed to a pseudo random number.
+///
+/// The result is deterministic, so rendering the same tilemap twice gives the same result.
+///
+/// ```
+///# use tilemap::utils::hash::hash2d;
+/// assert_eq!(hash2d(3, 4, 42), hash2d(3, 4, 42));
+/// assert_ne!(hash2d(3, 4, 42), hash2d(4, 3, 42));
+/// assert_ne!(hash2d(3, 4, 42), hash2d(3, 4, 43));
+/// ```
+pub fn hash2d(x: u32, y: u32, seed: u32) -> u32 {
+    let mut hash = seed ^ 0x9E37_79B9;
+    hash = (hash ^ x).wrapping_mul(0x85EB_CA6B);
+    hash = (hash.rotate_left(13) ^ y).wrapping_mul(0xC2B2_AE35);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x27D4_EB2F);
+    hash ^ (hash >> 15)
+}
+
+/// Hashes a 2d position & a seed to a pseudo random number between 0 & 1.
+///
+/// ```
+///# use tilemap::utils::hash::hash2d_unit;
+/// for x in 0..100 {
+///     let value = hash2d_unit(x, 7, 1);
+///     assert!(value >= 0.0 && value <= 1.0);
+/// }
+/// ```
+pub fn hash2d_unit(x: u32, y: u32, seed: u32) -> f32 {
+    hash2d(x, y, seed) as f32 / u32::MAX as f32
+}
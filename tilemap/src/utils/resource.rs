@@ -1,48 +1,342 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::Hash;
 
 pub trait Resource: Debug + Default {
     fn get_name(&self) -> &str;
 }
 
+/// The id of a [`Resource`] in a [`ResourceManager`].
+///
+/// Each type of resource has its own id type, so ids of different types can't be mixed up.
+pub trait ResourceId: Copy + Debug + Eq + Hash {
+    fn from_index(index: usize) -> Self;
+
+    fn get_index(&self) -> usize;
+}
+
+/// Manages resources, that are referenced by their id or their unique name.
+///
+/// Removed resources leave a tombstone behind, so the ids of the other resources stay valid.
+/// Invalid ids return the default resource.
 #[derive(Debug)]
-pub struct ResourceManager<T: Resource> {
+pub struct ResourceManager<I: ResourceId, T: Resource> {
     default: T,
-    resources: Vec<T>,
+    resources: Vec<Option<T>>,
+    /// The number of resources without the tombstones.
+    count: usize,
+    ids: HashMap<String, I>,
 }
 
-impl<T: Resource> ResourceManager<T> {
-    pub fn new(resources: Vec<T>, default: T) -> ResourceManager<T> {
-        ResourceManager { default, resources }
+impl<I: ResourceId, T: Resource> ResourceManager<I, T> {
+    /// Creates a manager from a list of resources, if their names are unique.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::floor::FloorStyle;
+    ///# use tilemap::math::color::{BLUE, GREEN, PINK};
+    ///# use tilemap::tilemap::tile::FloorId;
+    ///# use tilemap::utils::resource::ResourceManager;
+    /// let styles = vec![FloorStyle::new("grass", GREEN), FloorStyle::new("grass", BLUE)];
+    /// let result: anyhow::Result<ResourceManager<FloorId, _>> =
+    ///     ResourceManager::new(styles, FloorStyle::new("default", PINK));
+    ///
+    /// assert!(result.is_err());
+    /// ```
+    pub fn new(resources: Vec<T>, default: T) -> Result<ResourceManager<I, T>> {
+        let mut ids = HashMap::new();
+
+        for (id, resource) in resources.iter().enumerate() {
+            let name = resource.get_name();
+
+            if ids.insert(name.to_string(), I::from_index(id)).is_some() {
+                bail!("A resource with the name '{}' already exists", name);
+            }
+        }
+
+        Ok(ResourceManager {
+            default,
+            count: resources.len(),
+            resources: resources.into_iter().map(Some).collect(),
+            ids,
+        })
+    }
+
+    pub fn with_default(resources: Vec<T>) -> Result<ResourceManager<I, T>> {
+        Self::new(resources, T::default())
     }
 
-    pub fn with_default(resources: Vec<T>) -> ResourceManager<T> {
+    /// Creates a manager without resources, so all ids return the default resource.
+    pub fn empty(default: T) -> ResourceManager<I, T> {
         ResourceManager {
-            default: T::default(),
-            resources,
+            default,
+            resources: Vec::new(),
+            count: 0,
+            ids: HashMap::new(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.resources.is_empty()
+        self.len() == 0
     }
 
+    /// Returns the number of resources without the removed ones.
     pub fn len(&self) -> usize {
-        self.resources.len()
+        self.count
     }
 
-    pub fn get(&self, id: usize) -> &T {
-        self.resources.get(id).unwrap_or(&self.default)
+    pub fn get(&self, id: I) -> &T {
+        self.resources
+            .get(id.get_index())
+            .and_then(|resource| resource.as_ref())
+            .unwrap_or(&self.default)
     }
 
-    pub fn get_id(&self, name: &str) -> Option<usize> {
-        self.resources
-            .iter()
-            .enumerate()
-            .find(|(_i, r)| r.get_name().eq(name))
-            .map(|(i, _r)| i)
+    pub fn get_id(&self, name: &str) -> Option<I> {
+        self.ids.get(name).copied()
     }
 
+    /// Iterates over all resources in the order of their ids.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.resources.iter().flatten()
+    }
+
+    /// Returns the names of all resources in the order of their ids.
     pub fn get_names(&self) -> Vec<&str> {
-        self.resources.iter().map(|r| r.get_name()).collect()
+        self.iter().map(|r| r.get_name()).collect()
+    }
+
+    /// Adds a resource with a new name & returns its id.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::floor::FloorStyle;
+    ///# use tilemap::math::color::{BLUE, GREEN};
+    ///# use tilemap::tilemap::tile::FloorId;
+    ///# use tilemap::utils::resource::ResourceManager;
+    /// let mut manager = ResourceManager::with_default(vec![FloorStyle::new("grass", GREEN)]).unwrap();
+    ///
+    /// assert_eq!(manager.add(FloorStyle::new("water", BLUE)).unwrap(), FloorId(1));
+    /// assert_eq!(manager.get_id("water"), Some(FloorId(1)));
+    /// assert!(manager.add(FloorStyle::new("grass", BLUE)).is_err());
+    /// ```
+    pub fn add(&mut self, resource: T) -> Result<I> {
+        let name = resource.get_name();
+
+        if self.ids.contains_key(name) {
+            bail!("A resource with the name '{}' already exists", name);
+        }
+
+        let id = I::from_index(self.resources.len());
+        self.ids.insert(name.to_string(), id);
+        self.resources.push(Some(resource));
+        self.count += 1;
+
+        Ok(id)
+    }
+
+    /// Replaces an existing resource & returns the old one. The name may change, if it is still unique.
+    pub fn replace(&mut self, id: I, resource: T) -> Result<T> {
+        let name = resource.get_name();
+
+        match self.ids.get(name) {
+            Some(other) if *other != id => {
+                bail!("A resource with the name '{}' already exists", name)
+            }
+            _ => {}
+        }
+
+        let old = match self.resources.get_mut(id.get_index()) {
+            Some(Some(old)) => std::mem::replace(old, resource),
+            _ => bail!("There is no resource with id {}", id.get_index()),
+        };
+
+        self.remove_name(old.get_name(), id);
+        self.ids.insert(self.get(id).get_name().to_string(), id);
+
+        Ok(old)
+    }
+
+    /// Removes a resource & returns it. Its id is not reused & returns the default resource from now on.
+    pub fn remove(&mut self, id: I) -> Result<T> {
+        match self
+            .resources
+            .get_mut(id.get_index())
+            .and_then(|resource| resource.take())
+        {
+            Some(old) => {
+                self.remove_name(old.get_name(), id);
+                self.count -= 1;
+                Ok(old)
+            }
+            None => bail!("There is no resource with id {}", id.get_index()),
+        }
+    }
+
+    /// Removes a name from the index, unless it belongs to another resource with the same name.
+    fn remove_name(&mut self, name: &str, id: I) {
+        if self.ids.get(name) == Some(&id) {
+            self.ids.remove(name);
+        }
+    }
+
+    /// Removes the tombstones of removed resources, which changes the ids of the following resources.
+    ///
+    /// Returns the new id for each old id, which is *None* for removed resources.
+    /// This allows to update the ids stored elsewhere, like in a tilemap.
+    ///
+    /// ```
+    ///# use tilemap::renderer::style::floor::FloorStyle;
+    ///# use tilemap::math::color::{BLUE, GREEN, RED};
+    ///# use tilemap::tilemap::tile::FloorId;
+    ///# use tilemap::utils::resource::ResourceManager;
+    /// let mut manager = ResourceManager::with_default(vec![
+    ///     FloorStyle::new("grass", GREEN),
+    ///     FloorStyle::new("lava", RED),
+    ///     FloorStyle::new("water", BLUE),
+    /// ])
+    /// .unwrap();
+    /// manager.remove(FloorId(1)).unwrap();
+    ///
+    /// assert_eq!(manager.compact(), vec![Some(FloorId(0)), None, Some(FloorId(1))]);
+    /// assert_eq!(manager.get_id("water"), Some(FloorId(1)));
+    /// ```
+    pub fn compact(&mut self) -> Vec<Option<I>> {
+        let mut mapping = Vec::with_capacity(self.resources.len());
+        let mut resources = Vec::with_capacity(self.len());
+        self.ids.clear();
+
+        for resource in self.resources.drain(..) {
+            match resource {
+                Some(resource) => {
+                    let id = I::from_index(resources.len());
+                    self.ids.insert(resource.get_name().to_string(), id);
+                    resources.push(Some(resource));
+                    mapping.push(Some(id));
+                }
+                None => mapping.push(None),
+            }
+        }
+
+        self.count = resources.len();
+        self.resources = resources;
+
+        mapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::color::{BLUE, GREEN, PINK, RED};
+    use crate::renderer::style::floor::FloorStyle;
+    use crate::tilemap::tile::FloorId;
+
+    fn create_manager() -> ResourceManager<FloorId, FloorStyle> {
+        ResourceManager::with_default(vec![
+            FloorStyle::new("grass", GREEN),
+            FloorStyle::new("water", BLUE),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reject_duplicate_names() {
+        let result: Result<ResourceManager<FloorId, _>> = ResourceManager::with_default(vec![
+            FloorStyle::new("a", GREEN),
+            FloorStyle::new("a", BLUE),
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut manager = create_manager();
+
+        let old = manager
+            .replace(FloorId(1), FloorStyle::new("lava", RED))
+            .unwrap();
+
+        assert_eq!(old, FloorStyle::new("water", BLUE));
+        assert_eq!(manager.get(FloorId(1)), &FloorStyle::new("lava", RED));
+        assert_eq!(manager.get_id("lava"), Some(FloorId(1)));
+        assert_eq!(manager.get_id("water"), None);
+        assert_eq!(manager.get_names(), vec!["grass", "lava"]);
+    }
+
+    #[test]
+    fn test_replace_with_same_name() {
+        let mut manager = create_manager();
+
+        manager
+            .replace(FloorId(1), FloorStyle::new("water", RED))
+            .unwrap();
+
+        assert_eq!(manager.get(FloorId(1)), &FloorStyle::new("water", RED));
+        assert_eq!(manager.get_id("water"), Some(FloorId(1)));
+    }
+
+    #[test]
+    fn test_replace_rejects_duplicate_name() {
+        let mut manager = create_manager();
+
+        assert!(manager
+            .replace(FloorId(1), FloorStyle::new("grass", RED))
+            .is_err());
+        assert_eq!(manager.get(FloorId(1)), &FloorStyle::new("water", BLUE));
+    }
+
+    #[test]
+    fn test_replace_unknown_id() {
+        let mut manager = create_manager();
+
+        assert!(manager
+            .replace(FloorId(2), FloorStyle::new("lava", RED))
+            .is_err());
+        assert_eq!(manager.len(), 2);
+    }
+
+    #[test]
+    fn test_remove_keeps_ids() {
+        let mut manager = create_manager();
+
+        assert_eq!(
+            manager.remove(FloorId(0)).unwrap(),
+            FloorStyle::new("grass", GREEN)
+        );
+
+        assert_eq!(manager.len(), 1);
+        assert_eq!(manager.get(FloorId(0)).get_floor_color(), &PINK);
+        assert_eq!(manager.get(FloorId(1)), &FloorStyle::new("water", BLUE));
+        assert_eq!(manager.get_id("grass"), None);
+        assert_eq!(manager.get_id("water"), Some(FloorId(1)));
+        assert!(manager.remove(FloorId(0)).is_err());
+        assert!(manager
+            .replace(FloorId(0), FloorStyle::new("lava", RED))
+            .is_err());
+        assert_eq!(
+            manager.add(FloorStyle::new("grass", RED)).unwrap(),
+            FloorId(2)
+        );
+        assert_eq!(manager.len(), 2);
+    }
+
+    #[test]
+    fn test_len_after_compact() {
+        let mut manager = create_manager();
+        manager.add(FloorStyle::new("lava", RED)).unwrap();
+        manager.remove(FloorId(1)).unwrap();
+
+        assert_eq!(manager.len(), 2);
+
+        manager.compact();
+
+        assert_eq!(manager.len(), 2);
+        assert_eq!(manager.iter().count(), 2);
+
+        manager.remove(FloorId(0)).unwrap();
+        manager.remove(FloorId(1)).unwrap();
+
+        assert!(manager.is_empty());
     }
 }
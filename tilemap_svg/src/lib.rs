@@ -1 +1,2 @@
+pub mod print;
 pub mod renderer;
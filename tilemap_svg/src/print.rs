@@ -0,0 +1,373 @@
+use crate::renderer::{format_number, Svg, SvgBuilder};
+use anyhow::{bail, Result};
+use tilemap::math::point2d::Point2d;
+use tilemap::math::size2d::Size2d;
+use tilemap::renderer::style::StyleMgr;
+use tilemap::renderer::view::View;
+use tilemap::tilemap::tilemap2d::Tilemap2d;
+use tilemap::tilemap::visibility::VisibilityMask;
+
+/// The length of the alignment marks in millimeters.
+const MARK_LENGTH: f32 = 5.0;
+/// The gap between the alignment marks & the printed map in millimeters.
+const MARK_GAP: f32 = 1.0;
+/// The width of the alignment marks in millimeters.
+const MARK_WIDTH: f32 = 0.25;
+/// The font size of the page labels in millimeters.
+const LABEL_SIZE: f32 = 3.5;
+
+/// The size of the printed pages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaperSize {
+    A4,
+    Letter,
+    /// A custom size in millimeters.
+    Custom {
+        width: f32,
+        height: f32,
+    },
+}
+
+impl PaperSize {
+    /// Returns the width & height in portrait orientation in millimeters.
+    pub fn get_size(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::Custom { width, height } => (*width, *height),
+        }
+    }
+}
+
+/// Options to print a tilemap at a physical scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrintOptions {
+    /// The width of a tile in millimeters.
+    pub tile_size: f32,
+    pub paper: PaperSize,
+    pub landscape: bool,
+    /// The empty border of each page in millimeters.
+    pub margin: f32,
+    /// The number of tiles shown on both neighboring pages.
+    pub overlap: u32,
+}
+
+impl Default for PrintOptions {
+    /// One inch per tile on A4 paper.
+    fn default() -> Self {
+        PrintOptions {
+            tile_size: 25.4,
+            paper: PaperSize::A4,
+            landscape: false,
+            margin: 10.0,
+            overlap: 0,
+        }
+    }
+}
+
+/// A page of the printed tilemap.
+struct Page {
+    row: usize,
+    column: usize,
+    start: Point2d,
+    size: Size2d,
+    /// Does the page overlap with the next page to the right & below?
+    has_next: (bool, bool),
+}
+
+/// Splits the rendered tilemap over several pages, that are printed at a physical scale.
+///
+/// Each page shows whole tiles, so the tile grid lines up across the pages.
+/// The scale is derived from the width of a tile in the view, which fits top-down views best.
+/// Alignment marks show the corners of the map & dashed lines where the next pages start, if they overlap.
+pub fn export_pages(
+    view: &dyn View,
+    tilemap: &Tilemap2d,
+    visibility: Option<&VisibilityMask>,
+    styles: &StyleMgr,
+    options: &PrintOptions,
+) -> Result<Vec<Svg>> {
+    let tiles = tilemap.get_size();
+    let (paper_width, paper_height) = get_paper_size(options);
+    let printable_width = paper_width - 2.0 * options.margin;
+    let printable_height = paper_height - 2.0 * options.margin;
+
+    if options.tile_size <= 0.0 {
+        bail!("The tile size of {}mm is too small", options.tile_size);
+    } else if options.margin < 0.0 || printable_width <= 0.0 || printable_height <= 0.0 {
+        bail!(
+            "The margin of {}mm leaves no space on the page",
+            options.margin
+        );
+    }
+
+    let tile_pixels = view
+        .get_tile_viewport(tiles, Point2d::default(), Size2d::square(1), styles)
+        .get_size()
+        .width();
+    let scale = options.tile_size / tile_pixels as f32;
+    let fits = |size: Size2d| {
+        let viewport = view.get_tile_viewport(tiles, Point2d::default(), size, styles);
+        viewport.get_size().width() as f32 * scale <= printable_width
+            && viewport.get_size().height() as f32 * scale <= printable_height
+    };
+    let columns = (1..=tiles.width())
+        .take_while(|n| fits(Size2d::new(*n, 1)))
+        .last()
+        .unwrap_or(0);
+    let rows = (1..=tiles.height())
+        .take_while(|n| fits(Size2d::new(1, *n)))
+        .last()
+        .unwrap_or(0);
+
+    if columns == 0 || rows == 0 {
+        bail!(
+            "A tile of {}mm doesn't fit onto the page",
+            options.tile_size
+        );
+    }
+
+    let x_ranges = split_axis(tiles.width(), columns, options.overlap)?;
+    let y_ranges = split_axis(tiles.height(), rows, options.overlap)?;
+    let mut pages = Vec::new();
+
+    for (row, (y, height)) in y_ranges.iter().enumerate() {
+        for (column, (x, width)) in x_ranges.iter().enumerate() {
+            pages.push(Page {
+                row,
+                column,
+                start: Point2d::new(*x as i32, *y as i32),
+                size: Size2d::new(*width, *height),
+                has_next: (column + 1 < x_ranges.len(), row + 1 < y_ranges.len()),
+            });
+        }
+    }
+
+    let count = pages.len();
+
+    Ok(pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| {
+            let viewport = view.get_tile_viewport(tiles, page.start, page.size, styles);
+            let mut builder = SvgBuilder::new(viewport.get_size());
+            view.render_viewport(tilemap, visibility, &viewport, &mut builder, styles);
+
+            let width = viewport.get_size().width() as f32 * scale;
+            let height = viewport.get_size().height() as f32 * scale;
+            let mut lines = vec![format!(
+                "<svg width=\"{0}mm\" height=\"{1}mm\" viewBox=\"0 0 {0} {1}\" xmlns=\"http://www.w3.org/2000/svg\">",
+                format_number(paper_width),
+                format_number(paper_height),
+            )];
+
+            lines.extend(builder.finish().nest(options.margin, options.margin, width, height));
+            add_corner_marks(&mut lines, options.margin, width, height);
+
+            if options.overlap > 0 {
+                let end = Size2d::new(
+                    page.size.width().saturating_sub(options.overlap),
+                    page.size.height().saturating_sub(options.overlap),
+                );
+                let inner = view
+                    .get_tile_viewport(tiles, page.start, end, styles)
+                    .get_size();
+                let x = options.margin + inner.width() as f32 * scale;
+                let y = options.margin + inner.height() as f32 * scale;
+
+                if page.has_next.0 {
+                    add_line(&mut lines, (x, options.margin), (x, options.margin + height), true);
+                }
+
+                if page.has_next.1 {
+                    add_line(&mut lines, (options.margin, y), (options.margin + width, y), true);
+                }
+            }
+
+            lines.push(format!(
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">Page {} of {} (row {}, column {})</text>",
+                format_number(paper_width / 2.0),
+                format_number(paper_height - options.margin / 2.0),
+                format_number(LABEL_SIZE),
+                index + 1,
+                count,
+                page.row + 1,
+                page.column + 1,
+            ));
+            lines.push("</svg>".to_string());
+
+            Svg::new(lines)
+        })
+        .collect())
+}
+
+fn get_paper_size(options: &PrintOptions) -> (f32, f32) {
+    let (width, height) = options.paper.get_size();
+
+    if options.landscape {
+        (height, width)
+    } else {
+        (width, height)
+    }
+}
+
+/// Splits the tiles along an axis into ranges of pages, that share the overlapping tiles.
+///
+/// Returns the start & length of each range.
+fn split_axis(tiles: u32, tiles_per_page: u32, overlap: u32) -> Result<Vec<(u32, u32)>> {
+    if tiles <= tiles_per_page {
+        return Ok(vec![(0, tiles)]);
+    } else if overlap >= tiles_per_page {
+        bail!(
+            "An overlap of {} tiles is too large for {} tiles per page",
+            overlap,
+            tiles_per_page
+        );
+    }
+
+    let step = tiles_per_page - overlap;
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    loop {
+        let length = tiles_per_page.min(tiles - start);
+        ranges.push((start, length));
+
+        if start + length >= tiles {
+            return Ok(ranges);
+        }
+
+        start += step;
+    }
+}
+
+/// Adds marks pointing at the 4 corners of the map.
+fn add_corner_marks(lines: &mut Vec<String>, margin: f32, width: f32, height: f32) {
+    let length = MARK_LENGTH.min(margin - MARK_GAP);
+
+    if length <= 0.0 {
+        return;
+    }
+
+    for x in [margin, margin + width] {
+        for y in [margin, margin + height] {
+            let dx = if x > margin { 1.0 } else { -1.0 };
+            let dy = if y > margin { 1.0 } else { -1.0 };
+            let gap = MARK_GAP + length;
+
+            add_line(lines, (x + dx * MARK_GAP, y), (x + dx * gap, y), false);
+            add_line(lines, (x, y + dy * MARK_GAP), (x, y + dy * gap), false);
+        }
+    }
+}
+
+fn add_line(lines: &mut Vec<String>, start: (f32, f32), end: (f32, f32), is_dashed: bool) {
+    let dash = if is_dashed {
+        " stroke-dasharray=\"2,2\""
+    } else {
+        ""
+    };
+
+    lines.push(format!(
+        "  <path d=\"M{} {} {} {}\" stroke=\"#000000\" stroke-width=\"{}\"{}/>",
+        format_number(start.0),
+        format_number(start.1),
+        format_number(end.0),
+        format_number(end.1),
+        format_number(MARK_WIDTH),
+        dash,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tilemap::renderer::style::grid::GridStyle;
+    use tilemap::renderer::view::top_down::TopDownView;
+    use tilemap::tilemap::tile::{FloorId, Tile};
+
+    fn export(tiles: Size2d, options: &PrintOptions) -> Result<Vec<Svg>> {
+        let view = TopDownView::new(Size2d::square(10));
+        let tilemap = Tilemap2d::default(tiles, Tile::Floor(FloorId(0))).unwrap();
+        let styles =
+            StyleMgr::without_manager(vec![], vec![], vec![], vec![], GridStyle::default())
+                .unwrap();
+
+        export_pages(&view, &tilemap, None, &styles, options)
+    }
+
+    #[test]
+    fn test_split_axis() {
+        assert_eq!(split_axis(5, 7, 1).unwrap(), vec![(0, 5)]);
+        assert_eq!(split_axis(10, 7, 1).unwrap(), vec![(0, 7), (6, 4)]);
+        assert_eq!(split_axis(14, 7, 0).unwrap(), vec![(0, 7), (7, 7)]);
+        assert_eq!(
+            split_axis(15, 5, 2).unwrap(),
+            vec![(0, 5), (3, 5), (6, 5), (9, 5), (12, 3)]
+        );
+        assert!(split_axis(10, 7, 7).is_err());
+    }
+
+    #[test]
+    fn test_export_pages() {
+        let options = PrintOptions {
+            overlap: 1,
+            ..PrintOptions::default()
+        };
+
+        let pages = export(Size2d::new(10, 12), &options).unwrap();
+
+        assert_eq!(pages.len(), 4);
+
+        let first = pages[0].export();
+
+        assert!(first.starts_with(
+            "<svg width=\"210mm\" height=\"297mm\" viewBox=\"0 0 210 297\" xmlns=\"http://www.w3.org/2000/svg\">"
+        ));
+        assert!(first.contains(
+            "<svg x=\"10\" y=\"10\" width=\"177.8\" height=\"254\" viewBox=\"0 0 70 100\""
+        ));
+        assert!(first.contains("<path d=\"M162.4 10 162.4 264\" stroke=\"#000000\" stroke-width=\"0.25\" stroke-dasharray=\"2,2\"/>"));
+        assert!(first.contains(">Page 1 of 4 (row 1, column 1)</text>"));
+
+        let last = pages[3].export();
+
+        assert!(last.contains(
+            "<svg x=\"10\" y=\"10\" width=\"101.6\" height=\"76.2\" viewBox=\"0 0 40 30\""
+        ));
+        assert!(!last.contains("stroke-dasharray=\"2,2\""));
+        assert!(last.contains(">Page 4 of 4 (row 2, column 2)</text>"));
+    }
+
+    #[test]
+    fn test_landscape_letter() {
+        let options = PrintOptions {
+            paper: PaperSize::Letter,
+            landscape: true,
+            ..PrintOptions::default()
+        };
+
+        let pages = export(Size2d::new(11, 7), &options).unwrap();
+
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0]
+            .export()
+            .starts_with("<svg width=\"279.4mm\" height=\"215.9mm\""));
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let tiles = Size2d::square(2);
+        let too_large = PrintOptions {
+            tile_size: 300.0,
+            ..PrintOptions::default()
+        };
+        let too_wide = PrintOptions {
+            margin: 110.0,
+            ..PrintOptions::default()
+        };
+
+        assert!(export(tiles, &too_large).is_err());
+        assert!(export(tiles, &too_wide).is_err());
+    }
+}
@@ -1,10 +1,18 @@
-use anyhow::Result;
-use std::fs::File;
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
 use std::io::Write;
+use std::path::Path;
 use tilemap::math::color::Color;
 use tilemap::math::point2d::Point2d;
 use tilemap::math::size2d::Size2d;
+use tilemap::port::layer::Layer;
 use tilemap::port::renderer::Renderer;
+use tilemap::port::style::StyleRef;
+use tilemap::renderer::style::aab::Outline;
+use tilemap::renderer::style::grid::LineStyle;
+use tilemap::renderer::style::image::Image;
+use tilemap::renderer::style::pattern::Pattern;
 
 /// A valid [SVG](https://en.wikipedia.org/wiki/Scalable_Vector_Graphics).
 #[derive(Debug, PartialEq, Eq)]
@@ -13,6 +21,35 @@ pub struct Svg {
 }
 
 impl Svg {
+    pub(crate) fn new(lines: Vec<String>) -> Self {
+        Svg { lines }
+    }
+
+    /// Returns the lines of the svg as a nested svg element, that is placed at a position with a specific size.
+    pub(crate) fn nest(&self, x: f32, y: f32, width: f32, height: f32) -> Vec<String> {
+        let mut lines = Vec::with_capacity(self.lines.len());
+
+        for (index, line) in self.lines.iter().enumerate() {
+            if index == 0 {
+                lines.push(line.replacen(
+                    "<svg ",
+                    &format!(
+                        "  <svg x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" ",
+                        format_number(x),
+                        format_number(y),
+                        format_number(width),
+                        format_number(height)
+                    ),
+                    1,
+                ));
+            } else {
+                lines.push(format!("  {}", line));
+            }
+        }
+
+        lines
+    }
+
     pub fn export(&self) -> String {
         self.lines.join("\n")
     }
@@ -29,14 +66,178 @@ impl Svg {
     }
 }
 
+/// Options to change the generated [SVG](https://en.wikipedia.org/wiki/Scalable_Vector_Graphics).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Renders a named group per run of consecutive elements on the same [`Layer`].
+    /// This keeps the order of the painter's algorithm of the 3d views,
+    /// where solids, walls & nodes alternate. Later groups of a layer get a numbered id, e.g. "walls-2".
+    pub group_by_layer: bool,
+    /// Renders a css class per style & face instead of inline colors, so the svg can be re-themed.
+    /// A class keeps the first color of its style & face. Faces with another color,
+    /// e.g. because of [`lighting`](tilemap::tilemap::light::Lighting) or a color jitter,
+    /// fall back to inline colors.
+    pub use_classes: bool,
+    /// Embeds the image files as data uris instead of linking them, so the svg is self-contained.
+    /// Images, that can't be embedded, are linked & their errors are collected by [`SvgBuilder::get_errors`].
+    pub embed_images: bool,
+}
+
+/// A css class of the generated svg.
+#[derive(Debug, PartialEq, Eq)]
+struct CssClass {
+    name: String,
+    property: &'static str,
+    color: Color,
+}
+
+/// A pattern definition of the generated svg, which is mapped onto the tiles by an affine transformation.
+#[derive(Debug, PartialEq, Eq)]
+struct SvgPattern {
+    pattern: Pattern,
+    /// The tile of a pattern, that varies per tile.
+    tile: Point2d,
+    background: Color,
+    origin: Point2d,
+    x_axis: Point2d,
+    y_axis: Point2d,
+}
+
+/// Identifies the patterns, that can be reused for other tiles. This requires the same transformation,
+/// except a translation by whole tiles, & the same tile for patterns varying per tile.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct PatternKey {
+    pattern: Pattern,
+    /// The tile of a pattern, that varies per tile.
+    tile: Option<Point2d>,
+    background: Color,
+    x_axis: Point2d,
+    y_axis: Point2d,
+    /// The origin of the lattice, that contains the origins of all tiles translated by whole tiles.
+    lattice_origin: Point2d,
+}
+
+impl PatternKey {
+    /// Returns none for a degenerate transformation, whose pattern can't be reused.
+    fn new(
+        pattern: &Pattern,
+        tile: Point2d,
+        background: Color,
+        origin: Point2d,
+        x_axis: Point2d,
+        y_axis: Point2d,
+    ) -> Option<Self> {
+        let determinant = x_axis.x * y_axis.y - x_axis.y * y_axis.x;
+
+        if determinant == 0 {
+            return None;
+        }
+
+        // the origin in the coordinates of the axes, rounded down to whole tiles
+        let u = floor_div(origin.x * y_axis.y - origin.y * y_axis.x, determinant);
+        let v = floor_div(x_axis.x * origin.y - x_axis.y * origin.x, determinant);
+        let lattice_origin = Point2d::new(
+            origin.x - u * x_axis.x - v * y_axis.x,
+            origin.y - u * x_axis.y - v * y_axis.y,
+        );
+
+        Some(PatternKey {
+            pattern: pattern.clone(),
+            tile: pattern.varies_per_tile().then_some(tile),
+            background,
+            x_axis,
+            y_axis,
+            lattice_origin,
+        })
+    }
+}
+
+/// Divides & rounds towards negative infinity.
+fn floor_div(dividend: i32, divisor: i32) -> i32 {
+    if divisor > 0 {
+        dividend.div_euclid(divisor)
+    } else {
+        (-dividend).div_euclid(-divisor)
+    }
+}
+
+impl SvgPattern {
+    fn export(&self, id: usize, lines: &mut Vec<String>) {
+        lines.push(format!(
+            "    <pattern id=\"pattern{}\" patternUnits=\"userSpaceOnUse\" width=\"1\" height=\"1\" patternTransform=\"matrix({} {} {} {} {} {})\">",
+            id,
+            self.x_axis.x,
+            self.x_axis.y,
+            self.y_axis.x,
+            self.y_axis.y,
+            self.origin.x,
+            self.origin.y,
+        ));
+        lines.push(format!(
+            "      <rect width=\"1\" height=\"1\" fill=\"{}\"/>",
+            self.background.to_hex()
+        ));
+
+        for shape in self.pattern.get_shapes(self.background, self.tile) {
+            let points: Vec<String> = shape
+                .get_points()
+                .iter()
+                .map(|(u, v)| format!("{},{}", format_number(*u), format_number(*v)))
+                .collect();
+            lines.push(format!(
+                "      <polygon points=\"{}\" fill=\"{}\"/>",
+                points.join(" "),
+                shape.get_color().to_hex()
+            ));
+        }
+
+        lines.push("    </pattern>".to_string());
+    }
+}
+
 /// Builds a valid [SVG](https://en.wikipedia.org/wiki/Scalable_Vector_Graphics).
+///
+/// Floors with the same [`Pattern`] share one pattern definition,
+/// except for patterns varying per tile like [`Pattern::Noise`], which need one definition per tile.
 #[derive(Debug, PartialEq, Eq)]
 pub struct SvgBuilder {
     lines: Vec<String>,
+    /// The runs of elements on the same [`Layer`] in the order of rendering, if the output is grouped by layer.
+    layers: Option<Vec<(Layer, Vec<String>)>>,
+    layer: Layer,
+    /// The css classes, if the output uses them.
+    classes: Option<Vec<CssClass>>,
+    class: Option<String>,
+    patterns: Vec<SvgPattern>,
+    /// The index of each reusable pattern.
+    pattern_ids: HashMap<PatternKey, usize>,
+    /// The href of each image file, if the images are embedded.
+    images: Option<BTreeMap<String, String>>,
+    /// The errors of images, that couldn't be embedded.
+    errors: Vec<String>,
+    /// The opacity & polygons of consecutive shadows, which are rendered as one group,
+    /// so overlapping shadows darken only once.
+    shadows: Option<(String, Vec<String>)>,
 }
 
 impl SvgBuilder {
+    /// Returns a builder, that renders all elements in one flat list.
     pub fn new(size: Size2d) -> Self {
+        Self::with_options(size, SvgOptions::default())
+    }
+
+    /// Returns a builder, that renders a named group per [`Layer`].
+    pub fn with_layers(size: Size2d) -> Self {
+        Self::with_options(
+            size,
+            SvgOptions {
+                group_by_layer: true,
+                ..SvgOptions::default()
+            },
+        )
+    }
+
+    pub fn with_options(size: Size2d, options: SvgOptions) -> Self {
         let mut lines = Vec::new();
 
         lines.push(format!(
@@ -45,25 +246,252 @@ impl SvgBuilder {
             size.height()
         ));
 
-        Self { lines }
+        Self {
+            lines,
+            layers: options.group_by_layer.then(Vec::new),
+            layer: Layer::Overlays,
+            classes: options.use_classes.then(Vec::new),
+            class: None,
+            patterns: Vec::new(),
+            pattern_ids: HashMap::new(),
+            images: options.embed_images.then(BTreeMap::new),
+            errors: Vec::new(),
+            shadows: None,
+        }
+    }
+
+    /// Returns the errors of the images, that couldn't be embedded & are linked instead.
+    pub fn get_errors(&self) -> &[String] {
+        &self.errors
     }
 
     pub fn finish(mut self) -> Svg {
+        self.finish_shadows();
+        let mut header = Vec::new();
+
+        if let Some(classes) = self.classes.take().filter(|c| !c.is_empty()) {
+            header.push("  <style>".to_string());
+
+            for class in classes {
+                header.push(format!(
+                    "    .{} {{ {}: {}; }}",
+                    class.name,
+                    class.property,
+                    class.color.to_hex()
+                ));
+            }
+
+            header.push("  </style>".to_string());
+        }
+
+        if !self.patterns.is_empty() {
+            header.push("  <defs>".to_string());
+
+            for (id, pattern) in self.patterns.iter().enumerate() {
+                pattern.export(id, &mut header);
+            }
+
+            header.push("  </defs>".to_string());
+        }
+
+        self.lines.splice(1..1, header);
+
+        if let Some(layers) = self.layers.take() {
+            let mut counts: BTreeMap<Layer, usize> = BTreeMap::new();
+
+            for (layer, elements) in layers {
+                let count = counts.entry(layer).or_default();
+                *count += 1;
+
+                if *count == 1 {
+                    self.lines
+                        .push(format!("  <g id=\"{}\">", layer.get_name()));
+                } else {
+                    self.lines
+                        .push(format!("  <g id=\"{}-{}\">", layer.get_name(), count));
+                }
+
+                for element in elements {
+                    self.lines.push(format!("  {}", element));
+                }
+
+                self.lines.push("  </g>".to_string());
+            }
+        }
+
         self.lines.push("</svg>".to_string());
 
         Svg { lines: self.lines }
     }
+
+    fn add(&mut self, element: String) {
+        self.finish_shadows();
+        self.push(element);
+    }
+
+    fn push(&mut self, element: String) {
+        match &mut self.layers {
+            None => self.lines.push(element),
+            Some(layers) => match layers.last_mut() {
+                Some((layer, elements)) if *layer == self.layer => elements.push(element),
+                _ => layers.push((self.layer, vec![element])),
+            },
+        }
+    }
+
+    /// Renders the group of the current shadows.
+    fn finish_shadows(&mut self) {
+        if let Some((opacity, polygons)) = self.shadows.take() {
+            self.push(format!("  <g opacity=\"{}\">", opacity));
+
+            for polygon in polygons {
+                self.push(format!("  {}", polygon));
+            }
+
+            self.push("  </g>".to_string());
+        }
+    }
+
+    /// Returns the attribute for the color, which references a css class, if possible.
+    fn get_color_attribute(&mut self, property: &'static str, color: Color) -> String {
+        if let (Some(classes), Some(name)) = (&mut self.classes, &self.class) {
+            match classes.iter().find(|c| c.name.eq(name)) {
+                None => {
+                    classes.push(CssClass {
+                        name: name.clone(),
+                        property,
+                        color,
+                    });
+                    return format!("class=\"{}\"", name);
+                }
+                Some(class) if class.property == property && class.color == color => {
+                    return format!("class=\"{}\"", name);
+                }
+                _ => {}
+            }
+        }
+
+        format!("{}=\"{}\"", property, color.to_hex())
+    }
+
+    /// Returns the href of an image file, which is either its path or a data uri.
+    /// An image, that can't be embedded, is linked & its error is collected.
+    fn get_image_href(&mut self, path: &str) -> String {
+        let Some(images) = &mut self.images else {
+            return path.to_string();
+        };
+
+        if let Some(href) = images.get(path) {
+            return href.clone();
+        }
+
+        let href = match embed_image(path) {
+            Ok(href) => href,
+            Err(error) => {
+                self.errors
+                    .push(format!("Failed to embed image '{}': {:#}", path, error));
+                path.to_string()
+            }
+        };
+        images.insert(path.to_string(), href.clone());
+        href
+    }
+}
+
+/// Formats a number with up to 3 decimal places.
+pub(crate) fn format_number(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// Escapes the characters of a text, that have a special meaning in xml.
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes the characters of an attribute value, that have a special meaning in xml.
+fn escape_attribute(text: &str) -> String {
+    escape_text(text).replace('"', "&quot;")
+}
+
+/// Returns an image file as a data uri, e.g. "data:image/png;base64,iVBORw0KGgo...".
+fn embed_image(path: &str) -> Result<String> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    let mime = match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => bail!("Unknown image format of '{}'", path),
+    };
+    let bytes = fs::read(path)?;
+
+    Ok(format!("data:{};base64,{}", mime, encode_base64(&bytes)))
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes with the standard [Base64](https://en.wikipedia.org/wiki/Base64) alphabet & padding.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let byte = |index: usize| *chunk.get(index).unwrap_or(&0) as u32;
+        let bits = (byte(0) << 16) | (byte(1) << 8) | byte(2);
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(BASE64[((bits >> (18 - 6 * index)) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+
+    text
+}
+
+/// Returns the name of the css class for a style, e.g. "wall-brick-front".
+fn get_class_name(style: &StyleRef) -> String {
+    let mut name = format!("{}-{}", style.get_kind().get_name(), style.get_name());
+
+    if let Some(face) = style.get_face() {
+        name.push('-');
+        name.push_str(face.get_name());
+    }
+
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
 }
 
 impl Renderer for SvgBuilder {
     fn render_rectangle(&mut self, x: i32, y: i32, size: Size2d, color: Color) {
-        self.lines.push(format!(
-            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+        let fill = self.get_color_attribute("fill", color);
+        self.add(format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
             x,
             y,
             size.width(),
             size.height(),
-            color.to_hex(),
+            fill,
         ));
     }
 
@@ -75,36 +503,206 @@ impl Renderer for SvgBuilder {
         p3: Point2d,
         color: Color,
     ) {
-        self.lines.push(format!(
-            "  <polygon points=\"{},{} {},{} {},{} {},{}\" fill=\"{}\"/>",
-            p0.x,
-            p0.y,
-            p1.x,
-            p1.y,
-            p2.x,
-            p2.y,
-            p3.x,
-            p3.y,
-            color.to_hex(),
+        let fill = self.get_color_attribute("fill", color);
+        self.add(format!(
+            "  <polygon points=\"{},{} {},{} {},{} {},{}\" {}/>",
+            p0.x, p0.y, p1.x, p1.y, p2.x, p2.y, p3.x, p3.y, fill,
+        ));
+    }
+
+    fn render_polygon(&mut self, points: &[Point2d], color: Color) {
+        let fill = self.get_color_attribute("fill", color);
+        let points: Vec<String> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+        self.add(format!(
+            "  <polygon points=\"{}\" {}/>",
+            points.join(" "),
+            fill,
+        ));
+    }
+
+    fn render_pattern(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        p2: Point2d,
+        p3: Point2d,
+        pattern: &Pattern,
+        tile: Point2d,
+        background: Color,
+    ) {
+        let x_axis = p3 - p0;
+        let y_axis = p1 - p0;
+        let key = PatternKey::new(pattern, tile, background, p0, x_axis, y_axis);
+        let id = match key.as_ref().and_then(|key| self.pattern_ids.get(key)) {
+            Some(id) => *id,
+            None => {
+                let id = self.patterns.len();
+                self.patterns.push(SvgPattern {
+                    pattern: pattern.clone(),
+                    tile,
+                    background,
+                    origin: p0,
+                    x_axis,
+                    y_axis,
+                });
+
+                if let Some(key) = key {
+                    self.pattern_ids.insert(key, id);
+                }
+
+                id
+            }
+        };
+
+        self.add(format!(
+            "  <polygon points=\"{},{} {},{} {},{} {},{}\" fill=\"url(#pattern{})\"/>",
+            p0.x, p0.y, p1.x, p1.y, p2.x, p2.y, p3.x, p3.y, id,
         ));
     }
 
+    fn render_image(
+        &mut self,
+        p0: Point2d,
+        p1: Point2d,
+        _p2: Point2d,
+        p3: Point2d,
+        image: &Image,
+        _background: Color,
+    ) {
+        let x_axis = p3 - p0;
+        let y_axis = p1 - p0;
+        let transform = format!(
+            "matrix({} {} {} {} {} {})",
+            x_axis.x, x_axis.y, y_axis.x, y_axis.y, p0.x, p0.y,
+        );
+        let href = escape_attribute(&self.get_image_href(image.get_path()));
+
+        // the unit square of the image is mapped onto the rectangle
+        match image.get_region() {
+            None => self.add(format!(
+                "  <image href=\"{}\" width=\"1\" height=\"1\" preserveAspectRatio=\"none\" transform=\"{}\"/>",
+                href, transform,
+            )),
+            Some(region) => {
+                // svg 1.1 renders an image without a size as nothing
+                let size = image.get_size().unwrap_or(region.get_size());
+                self.add(format!(
+                    "  <g transform=\"{}\"><svg width=\"1\" height=\"1\" viewBox=\"{} {} {} {}\" preserveAspectRatio=\"none\"><image href=\"{}\" width=\"{}\" height=\"{}\"/></svg></g>",
+                    transform,
+                    region.get_x(),
+                    region.get_y(),
+                    region.get_size().width(),
+                    region.get_size().height(),
+                    href,
+                    size.width(),
+                    size.height(),
+                ))
+            }
+        }
+    }
+
     fn render_line(&mut self, p0: Point2d, p1: Point2d, color: Color) {
-        self.lines.push(format!(
-            "  <path d=\"M{} {} {} {}\" stroke=\"{}\" stroke-dasharray=\"1,4\"/>",
-            p0.x,
-            p0.y,
-            p1.x,
-            p1.y,
-            color.to_hex(),
+        let stroke = self.get_color_attribute("stroke", color);
+        self.add(format!(
+            "  <path d=\"M{} {} {} {}\" {} stroke-dasharray=\"1,4\"/>",
+            p0.x, p0.y, p1.x, p1.y, stroke,
         ));
     }
+
+    fn render_shadow(&mut self, points: &[Point2d], color: Color, opacity: f32) {
+        let opacity = format_number(opacity);
+
+        if self.shadows.as_ref().map(|(o, _)| o) != Some(&opacity) {
+            self.finish_shadows();
+        }
+
+        let fill = self.get_color_attribute("fill", color);
+        let points: Vec<String> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+        let polygon = format!("  <polygon points=\"{}\" {}/>", points.join(" "), fill);
+
+        self.shadows
+            .get_or_insert_with(|| (opacity, Vec::new()))
+            .1
+            .push(polygon);
+    }
+
+    fn render_styled_line(&mut self, p0: Point2d, p1: Point2d, style: &LineStyle) {
+        let stroke = self.get_color_attribute("stroke", style.get_color());
+        let mut attributes = format!("{} stroke-width=\"{}\"", stroke, style.get_width());
+
+        if !style.get_dash().is_empty() {
+            let dash: Vec<String> = style.get_dash().iter().map(|d| d.to_string()).collect();
+            attributes.push_str(&format!(" stroke-dasharray=\"{}\"", dash.join(",")));
+        }
+
+        if style.get_opacity() < 1.0 {
+            attributes.push_str(&format!(
+                " stroke-opacity=\"{}\"",
+                format_number(style.get_opacity())
+            ));
+        }
+
+        self.add(format!(
+            "  <path d=\"M{} {} {} {}\" {}/>",
+            p0.x, p0.y, p1.x, p1.y, attributes,
+        ));
+    }
+
+    fn render_outline(&mut self, points: &[Point2d], outline: &Outline) {
+        let stroke = self.get_color_attribute("stroke", outline.get_color());
+        let points: Vec<String> = points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+        self.add(format!(
+            "  <polygon points=\"{}\" fill=\"none\" {} stroke-width=\"{}\" stroke-linejoin=\"round\"/>",
+            points.join(" "),
+            stroke,
+            outline.get_width(),
+        ));
+    }
+
+    fn render_text(&mut self, center: Point2d, text: &str, size: u32, color: Color) {
+        let fill = self.get_color_attribute("fill", color);
+        self.add(format!(
+            "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" {}>{}</text>",
+            center.x,
+            center.y,
+            size,
+            fill,
+            escape_text(text),
+        ));
+    }
+
+    fn set_layer(&mut self, layer: Layer) {
+        if layer != self.layer {
+            self.finish_shadows();
+        }
+
+        self.layer = layer;
+    }
+
+    fn set_style(&mut self, style: StyleRef) {
+        self.class = Some(get_class_name(&style));
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tilemap::math::color::{ORANGE, PINK};
+    use tilemap::math::color::{BLACK, ORANGE, PINK};
+    use tilemap::math::side::Side;
+    use tilemap::port::style::{Face, StyleKind};
+    use tilemap::renderer::style::aab::BoxStyle;
+    use tilemap::renderer::style::grid::GridStyle;
+    use tilemap::renderer::style::image::ImageRegion;
+    use tilemap::renderer::style::node::NodeStyle;
+    use tilemap::renderer::style::solid::SolidStyle;
+    use tilemap::renderer::style::wall::WallStyle;
+    use tilemap::renderer::style::StyleMgr;
+    use tilemap::renderer::view::isometric::IsometricView;
+    use tilemap::renderer::view::View;
+    use tilemap::tilemap::border::{Border, WallId};
+    use tilemap::tilemap::tile::{FloorId, SolidId, Tile};
+    use tilemap::tilemap::tilemap2d::Tilemap2d;
+    use tilemap::tilemap::NodeId;
 
     #[test]
     fn test_empty_svg() {
@@ -130,4 +728,427 @@ mod tests {
 
         assert_eq!(&svg.export(), result);
     }
+
+    #[test]
+    fn test_styled_lines() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        builder.render_styled_line(
+            Point2d::new(0, 10),
+            Point2d::new(100, 10),
+            &LineStyle::solid(BLACK, 2),
+        );
+        builder.render_styled_line(
+            Point2d::new(10, 0),
+            Point2d::new(10, 150),
+            &LineStyle::new(PINK, 1, vec![2, 3], 0.5),
+        );
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <path d=\"M0 10 100 10\" stroke=\"#000000\" stroke-width=\"2\"/>
+  <path d=\"M10 0 10 150\" stroke=\"#FF0080\" stroke-width=\"1\" stroke-dasharray=\"2,3\" stroke-opacity=\"0.5\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_text() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        builder.render_text(Point2d::new(50, 20), "A<1>", 12, ORANGE);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <text x=\"50\" y=\"20\" font-size=\"12\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"#FFA500\">A&lt;1&gt;</text>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_polygon() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let points = [
+            Point2d::new(10, 20),
+            Point2d::new(0, 40),
+            Point2d::new(20, 50),
+            Point2d::new(30, 30),
+        ];
+        builder.render_polygon(&points, ORANGE);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <polygon points=\"10,20 0,40 20,50 30,30\" fill=\"#FFA500\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_group_consecutive_shadows() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let triangle = [Point2d::new(0, 0), Point2d::new(0, 10), Point2d::new(10, 0)];
+        builder.render_shadow(&triangle, BLACK, 0.5);
+        builder.render_shadow(&triangle, BLACK, 0.5);
+        builder.render_rectangle(10, 20, Size2d::new(30, 40), ORANGE);
+        builder.render_shadow(&triangle, BLACK, 0.25);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <g opacity=\"0.5\">
+    <polygon points=\"0,0 0,10 10,0\" fill=\"#000000\"/>
+    <polygon points=\"0,0 0,10 10,0\" fill=\"#000000\"/>
+  </g>
+  <rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"#FFA500\"/>
+  <g opacity=\"0.25\">
+    <polygon points=\"0,0 0,10 10,0\" fill=\"#000000\"/>
+  </g>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_outline() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let points = [
+            Point2d::new(10, 20),
+            Point2d::new(10, 60),
+            Point2d::new(40, 60),
+        ];
+        builder.render_outline(&points, &Outline::new(BLACK, 3));
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <polygon points=\"10,20 10,60 40,60\" fill=\"none\" stroke=\"#000000\" stroke-width=\"3\" stroke-linejoin=\"round\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_ignore_layers_without_grouping() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        builder.set_layer(Layer::Walls);
+        builder.render_rectangle(10, 20, Size2d::new(30, 40), ORANGE);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"#FFA500\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_merge_consecutive_elements_of_layer() {
+        let mut builder = SvgBuilder::with_layers(Size2d::new(100, 150));
+        builder.set_layer(Layer::Walls);
+        builder.render_rectangle(10, 20, Size2d::new(30, 40), ORANGE);
+        builder.render_rectangle(1, 2, Size2d::new(3, 4), PINK);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <g id=\"walls\">
+    <rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"#FFA500\"/>
+    <rect x=\"1\" y=\"2\" width=\"3\" height=\"4\" fill=\"#FF0080\"/>
+  </g>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_isometric_wall_in_front_of_solid_with_layers() {
+        // the wall is in front of the first solid & behind the second one
+        let solid = Tile::Solid(SolidId(0));
+        let tiles = vec![solid, Tile::Floor(FloorId(0)), solid];
+        let mut tilemap = Tilemap2d::new(Size2d::new(1, 3), tiles).unwrap();
+        tilemap.set_border(0, Side::Front, Border::Wall(WallId(0)));
+        let node = NodeStyle::new("node", BoxStyle::shaded(ORANGE), 2);
+        let wall = WallStyle::new("wall", BoxStyle::shaded(BLACK), NodeId(0), 2);
+        let styles = StyleMgr::without_manager(
+            vec![],
+            vec![node],
+            vec![SolidStyle::new("solid", BoxStyle::shaded(PINK))],
+            vec![wall],
+            GridStyle::default(),
+        )
+        .unwrap();
+        let viewer = IsometricView::new(10, 20);
+        let mut builder = SvgBuilder::with_layers(viewer.get_size(tilemap.get_size(), &styles));
+
+        viewer.render(&tilemap, None, &mut builder, &styles);
+
+        let svg = builder.finish().export();
+        let back_solid = svg.find("<g id=\"solids\">").unwrap();
+        let wall = svg.find("<g id=\"walls\">").unwrap();
+        let front_solid = svg.find("<g id=\"solids-2\">").unwrap();
+
+        assert!(back_solid < wall);
+        assert!(wall < front_solid);
+    }
+
+    #[test]
+    fn test_group_by_layer() {
+        let mut builder = SvgBuilder::with_layers(Size2d::new(100, 150));
+        builder.set_layer(Layer::Walls);
+        builder.render_rectangle(10, 20, Size2d::new(30, 40), ORANGE);
+        builder.set_layer(Layer::Floors);
+        builder.render_rectangle(50, 70, Size2d::new(35, 45), PINK);
+        builder.set_layer(Layer::Walls);
+        builder.render_rectangle(1, 2, Size2d::new(3, 4), PINK);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <g id=\"walls\">
+    <rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"#FFA500\"/>
+  </g>
+  <g id=\"floors\">
+    <rect x=\"50\" y=\"70\" width=\"35\" height=\"45\" fill=\"#FF0080\"/>
+  </g>
+  <g id=\"walls-2\">
+    <rect x=\"1\" y=\"2\" width=\"3\" height=\"4\" fill=\"#FF0080\"/>
+  </g>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_ignore_style_without_classes() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        builder.set_style(StyleRef::new(StyleKind::Floor, "grass"));
+        builder.render_rectangle(10, 20, Size2d::new(30, 40), ORANGE);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" fill=\"#FFA500\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_classes() {
+        let options = SvgOptions {
+            use_classes: true,
+            ..SvgOptions::default()
+        };
+        let mut builder = SvgBuilder::with_options(Size2d::new(100, 150), options);
+        let wall = StyleRef::new(StyleKind::Wall, "red brick");
+        builder.set_style(wall.with_face(Face::Top));
+        builder.render_rectangle(10, 20, Size2d::new(30, 40), ORANGE);
+        builder.set_style(wall.with_face(Face::Front));
+        builder.render_rectangle(50, 70, Size2d::new(35, 45), PINK);
+        builder.set_style(wall.with_face(Face::Top));
+        builder.render_rectangle(1, 2, Size2d::new(3, 4), ORANGE);
+        builder.render_rectangle(5, 6, Size2d::new(7, 8), PINK);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <style>
+    .wall-red-brick-top { fill: #FFA500; }
+    .wall-red-brick-front { fill: #FF0080; }
+  </style>
+  <rect x=\"10\" y=\"20\" width=\"30\" height=\"40\" class=\"wall-red-brick-top\"/>
+  <rect x=\"50\" y=\"70\" width=\"35\" height=\"45\" class=\"wall-red-brick-front\"/>
+  <rect x=\"1\" y=\"2\" width=\"3\" height=\"4\" class=\"wall-red-brick-top\"/>
+  <rect x=\"5\" y=\"6\" width=\"7\" height=\"8\" fill=\"#FF0080\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_pattern_key_of_same_lattice() {
+        let pattern = Pattern::Checkerboard {
+            color: BLACK,
+            cells: 2,
+        };
+        let p = Point2d::new;
+        let key = |origin| PatternKey::new(&pattern, p(0, 0), PINK, origin, p(10, 5), p(-10, 5));
+
+        assert_eq!(key(p(20, 0)), key(p(-20, -40)));
+        assert_eq!(key(p(20, 0)), key(p(30, 5)));
+        assert_ne!(key(p(20, 0)), key(p(25, 0)));
+        assert_eq!(
+            PatternKey::new(&pattern, p(0, 0), PINK, p(0, 0), p(10, 5), p(20, 10)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_noise_pattern_per_tile() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let pattern = Pattern::Noise {
+            cells: 2,
+            strength: 20,
+        };
+        let p = Point2d::new;
+        let (a, b, c, d) = (p(0, 0), p(0, 10), p(10, 10), p(10, 0));
+        builder.render_pattern(a, b, c, d, &pattern, p(0, 0), PINK);
+        builder.render_pattern(a, b, c, d, &pattern, p(0, 0), PINK);
+        builder.render_pattern(a + 10, b + 10, c + 10, d + 10, &pattern, p(1, 1), PINK);
+
+        assert_eq!(builder.patterns.len(), 2);
+    }
+
+    #[test]
+    fn test_reuse_pattern_for_tiles_of_same_lattice() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let pattern = Pattern::Checkerboard {
+            color: BLACK,
+            cells: 2,
+        };
+        let p = Point2d::new;
+        let (a, b, c) = (p(20, 0), p(10, 5), p(20, 10));
+        builder.render_pattern(a, b, c, p(30, 5), &pattern, p(0, 0), PINK);
+        builder.render_pattern(p(30, 5), c, p(30, 15), p(40, 10), &pattern, p(1, 0), PINK);
+        builder.render_pattern(
+            p(25, 0),
+            p(15, 5),
+            p(25, 10),
+            p(35, 5),
+            &pattern,
+            p(2, 0),
+            PINK,
+        );
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <defs>
+    <pattern id=\"pattern0\" patternUnits=\"userSpaceOnUse\" width=\"1\" height=\"1\" patternTransform=\"matrix(10 5 -10 5 20 0)\">
+      <rect width=\"1\" height=\"1\" fill=\"#FF0080\"/>
+      <polygon points=\"0.5,0 0.5,0.5 1,0.5 1,0\" fill=\"#000000\"/>
+      <polygon points=\"0,0.5 0,1 0.5,1 0.5,0.5\" fill=\"#000000\"/>
+    </pattern>
+    <pattern id=\"pattern1\" patternUnits=\"userSpaceOnUse\" width=\"1\" height=\"1\" patternTransform=\"matrix(10 5 -10 5 25 0)\">
+      <rect width=\"1\" height=\"1\" fill=\"#FF0080\"/>
+      <polygon points=\"0.5,0 0.5,0.5 1,0.5 1,0\" fill=\"#000000\"/>
+      <polygon points=\"0,0.5 0,1 0.5,1 0.5,0.5\" fill=\"#000000\"/>
+    </pattern>
+  </defs>
+  <polygon points=\"20,0 10,5 20,10 30,5\" fill=\"url(#pattern0)\"/>
+  <polygon points=\"30,5 20,10 30,15 40,10\" fill=\"url(#pattern0)\"/>
+  <polygon points=\"25,0 15,5 25,10 35,5\" fill=\"url(#pattern1)\"/>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_images() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let p = Point2d::new;
+        let sprite = Image::sprite(
+            "sheet.png",
+            Size2d::new(64, 48),
+            ImageRegion::new(16, 32, Size2d::new(16, 8)),
+        );
+        builder.render_image(
+            p(20, 0),
+            p(10, 5),
+            p(20, 10),
+            p(30, 5),
+            &Image::new("a&b.png"),
+            PINK,
+        );
+        builder.render_image(p(0, 20), p(0, 40), p(10, 40), p(10, 20), &sprite, PINK);
+        let svg = builder.finish();
+
+        let result = "<svg viewBox=\"0 0 100 150\" xmlns=\"http://www.w3.org/2000/svg\">
+  <image href=\"a&amp;b.png\" width=\"1\" height=\"1\" preserveAspectRatio=\"none\" transform=\"matrix(10 5 -10 5 20 0)\"/>
+  <g transform=\"matrix(10 0 0 20 0 20)\"><svg width=\"1\" height=\"1\" viewBox=\"16 32 16 8\" preserveAspectRatio=\"none\"><image href=\"sheet.png\" width=\"64\" height=\"48\"/></svg></g>
+</svg>";
+
+        assert_eq!(&svg.export(), result);
+    }
+
+    #[test]
+    fn test_embed_images() {
+        let path = std::env::temp_dir().join("tilemap_svg_test_embed.png");
+        fs::write(&path, b"foobar").unwrap();
+        let path = path.to_str().unwrap();
+        let options = SvgOptions {
+            embed_images: true,
+            ..SvgOptions::default()
+        };
+        let mut builder = SvgBuilder::with_options(Size2d::new(100, 150), options);
+        let p = Point2d::new;
+        builder.render_image(p(0, 0), p(0, 1), p(1, 1), p(1, 0), &Image::new(path), PINK);
+        builder.render_image(
+            p(0, 0),
+            p(0, 1),
+            p(1, 1),
+            p(1, 0),
+            &Image::new("x.png"),
+            PINK,
+        );
+        builder.render_image(
+            p(0, 0),
+            p(0, 1),
+            p(1, 1),
+            p(1, 0),
+            &Image::new("x.png"),
+            PINK,
+        );
+        assert_eq!(builder.get_errors().len(), 1);
+        assert!(builder.get_errors()[0].starts_with("Failed to embed image 'x.png': "));
+        let svg = builder.finish().export();
+
+        assert!(svg.contains("href=\"data:image/png;base64,Zm9vYmFy\""));
+        // a missing file is linked instead
+        assert!(svg.contains("href=\"x.png\""));
+    }
+
+    #[test]
+    fn test_encode_base64() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(b"foob"), "Zm9vYg==");
+    }
+
+    #[test]
+    fn test_noise_is_not_reused_for_other_tiles() {
+        let mut builder = SvgBuilder::new(Size2d::new(100, 150));
+        let pattern = Pattern::Noise {
+            cells: 2,
+            strength: 50,
+        };
+        let p = Point2d::new;
+        builder.render_pattern(
+            p(0, 0),
+            p(0, 10),
+            p(10, 10),
+            p(10, 0),
+            &pattern,
+            p(0, 0),
+            PINK,
+        );
+        builder.render_pattern(
+            p(10, 0),
+            p(10, 10),
+            p(20, 10),
+            p(20, 0),
+            &pattern,
+            p(1, 0),
+            PINK,
+        );
+        let svg = builder.finish().export();
+
+        assert!(svg.contains("fill=\"url(#pattern0)\""));
+        assert!(svg.contains("fill=\"url(#pattern1)\""));
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0.0), "0");
+        assert_eq!(format_number(-0.0001), "0");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(0.12345), "0.123");
+        assert_eq!(format_number(-1.25), "-1.25");
+    }
 }